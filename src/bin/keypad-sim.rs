@@ -0,0 +1,87 @@
+//! Simulate a 3x4 phone keypad in the terminal, entering text with multi-tap.
+//!
+//! Optionally, a file binds terminal keys to keypad positions, laid out as a
+//! grid (see `Bindings::parse`).
+//!
+//! ```text
+//! keypad-sim [bindings]
+//! ```
+
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+use std::{env, fs};
+
+use embedded_keypad::sim::{Bindings, Error, Simulator};
+use embedded_keypad::text::{self, MultiTap};
+use embedded_keypad::{keymap, Events, Key, Overflow};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+
+    let bindings = match &args[..] {
+        [_] => None,
+        [_, path] => match fs::read_to_string(path).map(|config| Bindings::parse(&config)) {
+            Ok(Ok(bindings)) => Some(bindings),
+            Ok(Err(err)) => {
+                eprintln!("invalid bindings in {}: {}", path, err);
+                return ExitCode::FAILURE;
+            }
+            Err(err) => {
+                eprintln!("could not read {}: {}", path, err);
+                return ExitCode::FAILURE;
+            }
+        },
+        _ => {
+            eprintln!("usage: keypad-sim [bindings]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(bindings) {
+        Ok(()) | Err(Error::Quit) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(bindings: Option<Bindings>) -> Result<(), Error> {
+    let mut keypad = Simulator::new(keymap::PHONE_3X4)?;
+
+    if let Some(bindings) = bindings {
+        keypad = keypad.with_bindings(bindings)?;
+    }
+
+    let start = Instant::now();
+    let clock = || start.elapsed().as_millis() as u32;
+
+    let mut events: Events<_, 8> = Events::new(keypad, Overflow::DropOldest);
+    let mut input: MultiTap<_, 64> = MultiTap::new(clock, 1000, text::ENGLISH)
+        .with_backspace(Key::Star)
+        .with_case_toggle(Key::Hash);
+
+    let mut last = None;
+
+    loop {
+        events.poll()?;
+
+        while let Some(event) = events.next_event() {
+            input.process(event);
+            last = Some(event);
+        }
+
+        input.poll();
+
+        let display = format!(
+            "Text: {}_\r\nCase: {:?}\r\nLast event: {:?}",
+            input.text(),
+            input.case(),
+            last
+        );
+
+        events.get_mut().set_display(&display)?;
+        thread::sleep(Duration::from_millis(10));
+    }
+}
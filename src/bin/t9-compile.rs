@@ -0,0 +1,50 @@
+//! Compile a list of words into a predictive text dictionary.
+//!
+//! The word list has one word per line, in order of preference. Blank lines
+//! and lines starting with `#` are ignored.
+//!
+//! ```text
+//! t9-compile words.txt dictionary.t9
+//! ```
+
+use std::process::ExitCode;
+use std::{env, fs};
+
+use embedded_keypad::text::t9;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+
+    let [_, input, output] = &args[..] else {
+        eprintln!("usage: t9-compile <words> <dictionary>");
+        return ExitCode::FAILURE;
+    };
+
+    let words = match fs::read_to_string(input) {
+        Ok(words) => words,
+        Err(err) => {
+            eprintln!("could not read {}: {}", input, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let words = words
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let data = match t9::compile(words) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(err) = fs::write(output, &data) {
+        eprintln!("could not write {}: {}", output, err);
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,305 @@
+use crate::{Clock, EventQueue, KeyEvent, Keys, Overflow};
+
+/// An event from a [Chords] recogniser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordEvent<A, K = u8> {
+    /// The keys of a chord were pressed together.
+    Chord(A),
+    /// A key event which was not part of a chord.
+    Key(KeyEvent<K>),
+}
+
+/// Recognises chords, where several keys are pressed together, from the
+/// [KeyEvent]s of a keypad. Up to `N` events are queued, and the releases of
+/// up to `N` chorded keys are hidden at once. Once `N` chorded keys are held,
+/// the releases of any more are reported.
+///
+/// When a key is pressed, every key pressed within the collection window is
+/// collected, up to four keys. The collection ends when the window passes, or
+/// when one of the collected keys is released. If the collected keys match a
+/// chord in the table, in any order, the chord's action is reported and the
+/// keys' releases are hidden. Otherwise, the keys are reported as pressed in
+/// the order they were pressed.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Clone, Copy)]
+/// enum Action {
+///     Reset,
+///     Menu,
+/// }
+///
+/// const CHORDS: &[(Keys<Key>, Action)] = &[
+///     (Keys::Two(Key::Star, Key::Hash), Action::Reset),
+///     (Keys::Two(Key::A, Key::D), Action::Menu),
+/// ];
+///
+/// // Keys pressed within 50ms of each other are collected.
+/// let mut chords: Chords<_, _, 8, Key> = Chords::new(|| timer.millis(), 50, CHORDS);
+///
+/// while let Some(event) = events.next_event() {
+///     chords.process(event);
+/// }
+///
+/// chords.poll();
+///
+/// while let Some(event) = chords.next_event() {
+///     match event {
+///         ChordEvent::Chord(Action::Reset) => reset(),
+///         ChordEvent::Chord(Action::Menu) => menu(),
+///         ChordEvent::Key(KeyEvent::Pressed(key)) => println!("Pressed {}.", key),
+///         _ => (),
+///     }
+/// }
+/// ```
+pub struct Chords<'a, C: Clock, A: Copy, const N: usize, K: Copy = u8> {
+    clock: C,
+    window: u32,
+    chords: &'a [(Keys<K>, A)],
+    collected: [Option<K>; 4],
+    started: u32,
+    chorded: [Option<K>; N],
+    queue: EventQueue<ChordEvent<A, K>, N>,
+}
+
+impl<'a, C: Clock, A: Copy, const N: usize, K: Copy + PartialEq> Chords<'a, C, A, N, K> {
+    /// Create a recogniser which collects keys pressed within `window` ticks
+    /// of the first, and matches them against a table of chords.
+    pub fn new(clock: C, window: u32, chords: &'a [(Keys<K>, A)]) -> Self {
+        Self {
+            clock,
+            window,
+            chords,
+            collected: [None; 4],
+            started: 0,
+            chorded: [None; N],
+            queue: EventQueue::new(Overflow::DropOldest),
+        }
+    }
+
+    /// Update the chord in progress with an event from the keypad.
+    pub fn process(&mut self, event: KeyEvent<K>) {
+        let key = event.key();
+
+        if let KeyEvent::Pressed(key) = event {
+            if self.collected[0].is_none() {
+                self.started = self.clock.now();
+            }
+
+            match self.collected.iter_mut().find(|slot| slot.is_none()) {
+                Some(slot) => *slot = Some(key),
+                None => {
+                    self.resolve();
+                    self.started = self.clock.now();
+                    self.collected[0] = Some(key);
+                }
+            }
+
+            return;
+        }
+
+        if self.collected.contains(&Some(key)) {
+            self.resolve();
+        }
+
+        match self.chorded.iter_mut().find(|slot| **slot == Some(key)) {
+            Some(slot) if matches!(event, KeyEvent::Released(_)) => *slot = None,
+            Some(_) => (),
+            None => {
+                self.queue.push(ChordEvent::Key(event));
+            }
+        }
+    }
+
+    /// End the collection once the window has passed. This should be called
+    /// regularly, even when there are no new events.
+    pub fn poll(&mut self) {
+        if self.collected[0].is_some() && self.clock.since(self.started) >= self.window {
+            self.resolve();
+        }
+    }
+
+    /// Remove the oldest event from the queue.
+    pub fn next_event(&mut self) -> Option<ChordEvent<A, K>> {
+        self.queue.pop()
+    }
+
+    /// Report the collected keys, either as a chord or as individual presses.
+    fn resolve(&mut self) {
+        let collected = core::mem::take(&mut self.collected);
+
+        let Some(keys) = Keys::collect(collected.into_iter().flatten()) else {
+            return;
+        };
+
+        let chord = self.chords.iter().find(|(chord, _)| chord.matches(&keys));
+
+        match chord {
+            Some(&(_, action)) => {
+                self.queue.push(ChordEvent::Chord(action));
+
+                for key in keys.as_array().into_iter().flatten() {
+                    if let Some(slot) = self.chorded.iter_mut().find(|slot| slot.is_none()) {
+                        *slot = Some(key);
+                    }
+                }
+            }
+            None => {
+                for key in keys.as_array().into_iter().flatten() {
+                    self.queue.push(ChordEvent::Key(KeyEvent::Pressed(key)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::testing::drain;
+
+    const CHORDS: &[(Keys, char)] = &[(Keys::Two(1, 2), 'a'), (Keys::Three(1, 2, 3), 'b')];
+
+    #[test]
+    fn chord_within_window() {
+        let now = Cell::new(0);
+        let mut chords: Chords<_, _, 8> = Chords::new(|| now.get(), 50, CHORDS);
+
+        chords.process(KeyEvent::Pressed(2));
+        now.set(49);
+        chords.process(KeyEvent::Pressed(1));
+        chords.poll();
+
+        // Still collecting, in case a third key is pressed.
+        assert_eq!(chords.next_event(), None);
+
+        now.set(50);
+        chords.poll();
+        chords.process(KeyEvent::Released(1));
+        chords.process(KeyEvent::Released(2));
+
+        assert_eq!(
+            drain(|| chords.next_event()),
+            [Some(ChordEvent::Chord('a')), None, None, None]
+        );
+    }
+
+    #[test]
+    fn release_ends_collection() {
+        let now = Cell::new(0);
+        let mut chords: Chords<_, _, 8> = Chords::new(|| now.get(), 50, CHORDS);
+
+        chords.process(KeyEvent::Pressed(1));
+        chords.process(KeyEvent::Pressed(2));
+        chords.process(KeyEvent::Pressed(3));
+        chords.process(KeyEvent::Released(3));
+        chords.process(KeyEvent::Released(1));
+        chords.process(KeyEvent::Released(2));
+
+        assert_eq!(
+            drain(|| chords.next_event()),
+            [Some(ChordEvent::Chord('b')), None, None, None]
+        );
+    }
+
+    #[test]
+    fn keys_outside_window() {
+        let now = Cell::new(0);
+        let mut chords: Chords<_, _, 8> = Chords::new(|| now.get(), 50, CHORDS);
+
+        chords.process(KeyEvent::Pressed(1));
+        now.set(50);
+        chords.poll();
+        chords.process(KeyEvent::Pressed(2));
+        now.set(100);
+        chords.poll();
+        chords.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| chords.next_event()),
+            [
+                Some(ChordEvent::Key(KeyEvent::Pressed(1))),
+                Some(ChordEvent::Key(KeyEvent::Pressed(2))),
+                Some(ChordEvent::Key(KeyEvent::Released(1))),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_chord() {
+        let now = Cell::new(0);
+        let mut chords: Chords<_, _, 8> = Chords::new(|| now.get(), 50, CHORDS);
+
+        chords.process(KeyEvent::Pressed(1));
+        chords.process(KeyEvent::Pressed(4));
+        chords.process(KeyEvent::Released(4));
+
+        assert_eq!(
+            drain(|| chords.next_event()),
+            [
+                Some(ChordEvent::Key(KeyEvent::Pressed(1))),
+                Some(ChordEvent::Key(KeyEvent::Pressed(4))),
+                Some(ChordEvent::Key(KeyEvent::Released(4))),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn releases_are_hidden_until_keys_are_released() {
+        let now = Cell::new(0);
+        let mut chords: Chords<_, _, 8> = Chords::new(|| now.get(), 50, CHORDS);
+
+        chords.process(KeyEvent::Pressed(1));
+        chords.process(KeyEvent::Pressed(2));
+        now.set(50);
+        chords.poll();
+        chords.process(KeyEvent::Repeated(1));
+        chords.process(KeyEvent::Released(1));
+        chords.process(KeyEvent::Released(2));
+
+        // Keys are reported again once released.
+        chords.process(KeyEvent::Pressed(1));
+        now.set(100);
+        chords.poll();
+        chords.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| chords.next_event()),
+            [
+                Some(ChordEvent::Chord('a')),
+                Some(ChordEvent::Key(KeyEvent::Pressed(1))),
+                Some(ChordEvent::Key(KeyEvent::Released(1))),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn releases_past_n_are_reported() {
+        let now = Cell::new(0);
+        let chords = &[(Keys::Three(1, 2, 3), 'a')];
+        let mut chords: Chords<_, _, 2> = Chords::new(|| now.get(), 50, chords);
+
+        chords.process(KeyEvent::Pressed(1));
+        chords.process(KeyEvent::Pressed(2));
+        chords.process(KeyEvent::Pressed(3));
+        now.set(50);
+        chords.poll();
+        chords.process(KeyEvent::Released(1));
+        chords.process(KeyEvent::Released(2));
+        chords.process(KeyEvent::Released(3));
+
+        assert_eq!(
+            drain(|| chords.next_event()),
+            [
+                Some(ChordEvent::Chord('a')),
+                Some(ChordEvent::Key(KeyEvent::Released(3)))
+            ]
+        );
+    }
+}
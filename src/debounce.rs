@@ -0,0 +1,126 @@
+use crate::{Clock, Keypad, Keys};
+
+/// A keypad which only reports a change in the keys pressed once it has been
+/// stable for a given number of ticks. This filters out contact bounce, which
+/// can otherwise cause a single press to be read as several.
+///
+/// Both presses and releases are debounced, so a [Debounced] keypad reports
+/// keys a little later than they were pressed or released.
+///
+/// # Examples
+///
+/// ```ignore
+/// // Keys must be stable for 20ms before they are reported.
+/// let mut keypad = Debounced::new(keypad, || timer.millis(), 20);
+///
+/// if let Some(key) = keypad.read()? {
+///     println!("Got key: {}.", key);
+/// }
+/// ```
+pub struct Debounced<K: Keypad, C: Clock> {
+    keypad: K,
+    clock: C,
+    duration: u32,
+    stable: Option<Keys<K::Key>>,
+    pending: Option<Keys<K::Key>>,
+    since: u32,
+}
+
+impl<K: Keypad, C: Clock> Debounced<K, C> {
+    /// Debounce a keypad, requiring keys to be stable for `duration` ticks of
+    /// the clock.
+    pub fn new(keypad: K, clock: C, duration: u32) -> Self {
+        let since = clock.now();
+
+        Self {
+            keypad,
+            clock,
+            duration,
+            stable: None,
+            pending: None,
+            since,
+        }
+    }
+
+    /// Release the underlying keypad.
+    pub fn into_inner(self) -> K {
+        self.keypad
+    }
+
+    fn update(&mut self) -> Result<Option<Keys<K::Key>>, K::Error> {
+        let keys = self.keypad.read_multi()?;
+
+        if keys != self.pending {
+            self.pending = keys;
+            self.since = self.clock.now();
+        }
+
+        if self.pending != self.stable && self.clock.since(self.since) >= self.duration {
+            self.stable = self.pending;
+        }
+
+        Ok(self.stable)
+    }
+}
+
+impl<K: Keypad, C: Clock> Keypad for Debounced<K, C> {
+    type Key = K::Key;
+    type Error = K::Error;
+
+    fn key_is_pressed(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.update()?.is_some())
+    }
+
+    fn read(&mut self) -> Result<Option<K::Key>, Self::Error> {
+        Ok(self.update()?.and_then(|keys| keys.as_array()[0]))
+    }
+
+    fn read_multi(&mut self) -> Result<Option<Keys<K::Key>>, Self::Error> {
+        self.update()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::mock::Matrix;
+    use crate::GpioKeypad;
+
+    #[test]
+    fn filters_bounce() {
+        let matrix = Matrix::<3, 4>::new();
+        let now = Cell::new(0);
+        let mut keypad = Debounced::new(
+            GpioKeypad::new(matrix.columns(), matrix.rows()),
+            || now.get(),
+            5,
+        );
+
+        matrix.press_bouncing(1, 1, 6);
+
+        let mut reads = [None; 12];
+
+        for read in reads.iter_mut() {
+            *read = keypad.read().unwrap();
+            now.set(now.get() + 1);
+        }
+
+        let pressed_at = reads.iter().position(Option::is_some).unwrap();
+        assert!(pressed_at >= 5);
+        assert!(reads[pressed_at..].iter().all(|&read| read == Some(4)));
+    }
+
+    #[test]
+    fn bounce_is_visible_without_debouncing() {
+        let matrix = Matrix::<3, 4>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        matrix.press_bouncing(1, 1, 6);
+
+        let reads = [(); 6].map(|_| keypad.read().unwrap());
+        assert!(reads.contains(&None) && reads.contains(&Some(4)));
+        assert_eq!(keypad.read(), Ok(Some(4)));
+    }
+}
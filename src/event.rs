@@ -0,0 +1,300 @@
+use crate::{Keypad, Keys};
+
+/// A change in the state of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent<K = u8> {
+    /// The key was pressed.
+    Pressed(K),
+    /// The key was released.
+    Released(K),
+    /// The key is still held, and should be treated as though it was pressed
+    /// again. See [AutoRepeat](crate::AutoRepeat).
+    Repeated(K),
+}
+
+impl<K: Copy> KeyEvent<K> {
+    /// The key which changed state.
+    pub fn key(&self) -> K {
+        match *self {
+            KeyEvent::Pressed(key) | KeyEvent::Released(key) | KeyEvent::Repeated(key) => key,
+        }
+    }
+}
+
+/// What an [EventQueue] does with a new event when it is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Discard the new event, keeping the events already queued.
+    #[default]
+    DropNewest,
+    /// Discard the oldest queued event to make room for the new event.
+    DropOldest,
+}
+
+/// A first-in, first-out queue with space for `N` events.
+#[derive(Debug, Clone)]
+pub struct EventQueue<T: Copy, const N: usize> {
+    buf: [Option<T>; N],
+    head: usize,
+    len: usize,
+    overflow: Overflow,
+    dropped: usize,
+}
+
+impl<T: Copy, const N: usize> EventQueue<T, N> {
+    /// Create an empty queue, which handles overflow using the given policy.
+    pub const fn new(overflow: Overflow) -> Self {
+        Self {
+            buf: [None; N],
+            head: 0,
+            len: 0,
+            overflow,
+            dropped: 0,
+        }
+    }
+
+    /// Add an event to the back of the queue. Returns false if an event was
+    /// dropped because the queue was full.
+    pub fn push(&mut self, event: T) -> bool {
+        let full = self.len == N;
+
+        if full {
+            self.dropped += 1;
+
+            if N == 0 || self.overflow == Overflow::DropNewest {
+                return false;
+            }
+
+            self.pop();
+        }
+
+        self.buf[(self.head + self.len) % N] = Some(event);
+        self.len += 1;
+        !full
+    }
+
+    /// Remove the event at the front of the queue.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let event = self.buf[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        event
+    }
+
+    /// The event at the front of the queue, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        match self.len {
+            0 => None,
+            _ => self.buf[self.head].as_ref(),
+        }
+    }
+
+    /// Iterate over the events in the queue, from front to back.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len).filter_map(move |i| self.buf[(self.head + i) % N].as_ref())
+    }
+
+    /// The number of events in the queue.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the queue contains no events.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if no more events can be added without dropping one.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The number of events which have been dropped because the queue was
+    /// full, since it was created or last cleared.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Remove every event from the queue, and reset the dropped count.
+    pub fn clear(&mut self) {
+        self.buf = [None; N];
+        self.head = 0;
+        self.len = 0;
+        self.dropped = 0;
+    }
+}
+
+/// A keypad which reports [KeyEvent]s when keys are pressed and released,
+/// rather than the keys which are currently held. Each call to
+/// [Events::poll] reads the keypad and queues an event for every key which
+/// has changed since the previous call. Up to `N` events are queued, after
+/// which the [Overflow] policy applies.
+///
+/// Released events are queued before pressed events from the same read.
+///
+/// Events are found by comparing the keys reported by [Keypad::read_multi],
+/// so they are only as accurate as it is. A [GpioKeypad](crate::GpioKeypad)
+/// reports one key per column, preferring the highest row, so pressing a
+/// second key in the same column as a held key reports the held key as
+/// released. Where keys share a column, use
+/// [GpioKeypad::read_set](crate::GpioKeypad::read_set) to find every key.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut events: Events<_, 8> = Events::new(keypad, Overflow::DropOldest);
+///
+/// loop {
+///     events.poll()?;
+///
+///     while let Some(event) = events.next_event() {
+///         match event {
+///             KeyEvent::Pressed(key) => println!("Pressed {}.", key),
+///             KeyEvent::Released(key) => println!("Released {}.", key),
+///             _ => (),
+///         }
+///     }
+/// }
+/// ```
+pub struct Events<K: Keypad, const N: usize> {
+    keypad: K,
+    held: Option<Keys<K::Key>>,
+    queue: EventQueue<KeyEvent<K::Key>, N>,
+}
+
+impl<K: Keypad, const N: usize> Events<K, N> {
+    /// Wrap a keypad, with an empty queue which handles overflow using the
+    /// given policy. Passing `Overflow::default()` gives
+    /// [Overflow::DropNewest], which keeps the events already queued.
+    pub fn new(keypad: K, overflow: Overflow) -> Self {
+        Self {
+            keypad,
+            held: None,
+            queue: EventQueue::new(overflow),
+        }
+    }
+
+    /// Release the underlying keypad.
+    pub fn into_inner(self) -> K {
+        self.keypad
+    }
+
+    /// A mutable reference to the underlying keypad.
+    pub fn get_mut(&mut self) -> &mut K {
+        &mut self.keypad
+    }
+
+    /// Read the keypad, queueing an event for each key which was pressed or
+    /// released since the previous read.
+    pub fn poll(&mut self) -> Result<(), K::Error> {
+        let keys = self.keypad.read_multi()?;
+        let held = core::mem::replace(&mut self.held, keys);
+
+        let was_held = |key| held.is_some_and(|held| held.includes(key));
+        let is_held = |key| keys.is_some_and(|keys| keys.includes(key));
+
+        for key in held.iter().flat_map(Keys::as_array).flatten() {
+            if !is_held(key) {
+                self.queue.push(KeyEvent::Released(key));
+            }
+        }
+
+        for key in keys.iter().flat_map(Keys::as_array).flatten() {
+            if !was_held(key) {
+                self.queue.push(KeyEvent::Pressed(key));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the oldest event from the queue.
+    pub fn next_event(&mut self) -> Option<KeyEvent<K::Key>> {
+        self.queue.pop()
+    }
+
+    /// The queue of events which have not yet been taken.
+    pub fn queue(&self) -> &EventQueue<KeyEvent<K::Key>, N> {
+        &self.queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Matrix, MockKeypad, Step};
+    use crate::GpioKeypad;
+
+    #[test]
+    fn releases_are_queued_before_presses() {
+        let keypad = MockKeypad::new(&[
+            Step::Press(1),
+            Step::Press(2),
+            Step::Set(Some(Keys::Two(2, 3))),
+            Step::Set(None),
+        ]);
+        let mut events: Events<_, 8> = Events::new(keypad, Overflow::DropNewest);
+
+        for _ in 0..4 {
+            events.poll().unwrap();
+        }
+
+        let expected = [
+            KeyEvent::Pressed(1),
+            KeyEvent::Pressed(2),
+            KeyEvent::Released(1),
+            KeyEvent::Pressed(3),
+            KeyEvent::Released(2),
+            KeyEvent::Released(3),
+        ];
+
+        assert!(events.queue().iter().eq(expected.iter()));
+        events.into_inner().assert_finished();
+    }
+
+    #[test]
+    fn overflow() {
+        let mut newest: EventQueue<u8, 2> = EventQueue::new(Overflow::DropNewest);
+        let mut oldest: EventQueue<u8, 2> = EventQueue::new(Overflow::DropOldest);
+
+        for event in 1..=3 {
+            newest.push(event);
+            oldest.push(event);
+        }
+
+        assert_eq!(
+            (newest.pop(), newest.pop(), newest.dropped()),
+            (Some(1), Some(2), 1)
+        );
+        assert_eq!(
+            (oldest.pop(), oldest.pop(), oldest.dropped()),
+            (Some(2), Some(3), 1)
+        );
+        assert!(oldest.is_empty());
+    }
+
+    #[test]
+    fn keys_in_same_column_replace_each_other() {
+        let matrix = Matrix::<2, 2>::new();
+        let keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+        let mut events: Events<_, 8> = Events::new(keypad, Overflow::DropNewest);
+
+        matrix.press(0, 0);
+        events.poll().unwrap();
+        matrix.press(1, 0);
+        events.poll().unwrap();
+
+        // Key 0 is still held, but is hidden by key 2 in the row below.
+        let expected = [
+            KeyEvent::Pressed(0),
+            KeyEvent::Released(0),
+            KeyEvent::Pressed(2),
+        ];
+
+        assert!(events.queue().iter().eq(expected.iter()));
+    }
+}
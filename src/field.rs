@@ -0,0 +1,793 @@
+//! Entry of numbers, times and dates using the keys of a numeric keypad.
+
+use core::fmt::{self, Write};
+
+use heapless::String;
+
+use crate::{Key, KeyEvent};
+
+/// The outcome of a key press in a [Field].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldEvent<T> {
+    /// The text was changed.
+    Changed,
+    /// The key was not accepted, because it would make the value invalid.
+    Rejected,
+    /// Enter was pressed and the text is a valid value.
+    Entered(T),
+    /// Enter was pressed but the text is incomplete or out of range.
+    Invalid,
+}
+
+/// The format of the value entered in a [Field], which decides which keys are
+/// accepted as the text is typed, and converts the text to a value.
+pub trait Format {
+    /// The type of the value entered.
+    type Value;
+
+    /// A separator which is inserted between groups of digits as they are
+    /// typed, and removed along with the digit after it.
+    const SEPARATOR: Option<char> = None;
+
+    /// Apply a key press to the text. Returns false if the key is not used by
+    /// the format, or would make the text invalid.
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool;
+
+    /// Convert complete text to a value. Returns [None] if the text is
+    /// incomplete or out of range.
+    fn parse(&self, text: &str) -> Option<Self::Value>;
+
+    /// Write a value as text.
+    fn write<W: Write>(&self, value: &Self::Value, text: &mut W) -> fmt::Result;
+}
+
+/// An input field which accepts a value in the given [Format], with up to `N`
+/// bytes of text. The text is validated as each key is pressed, and the value
+/// is returned when enter is pressed.
+///
+/// By default, [Key::Hash] enters the value, [Key::Star] removes the last
+/// character and [Key::Clear] removes every character.
+///
+/// # Examples
+///
+/// ```ignore
+/// use embedded_keypad::field::{Field, FieldEvent, Integer};
+///
+/// let mut volume: Field<_, 4> = Field::new(Integer::new(0, 100)).with_value(&50);
+///
+/// while let Some(event) = events.next_event() {
+///     match volume.process(event) {
+///         Some(FieldEvent::Entered(value)) => set_volume(value),
+///         Some(FieldEvent::Rejected) => beep(),
+///         _ => display.show(volume.text()),
+///     }
+/// }
+/// ```
+pub struct Field<F: Format, const N: usize> {
+    format: F,
+    enter: Key,
+    backspace: Key,
+    clear: Key,
+    text: String<N>,
+    saved: String<N>,
+}
+
+impl<F: Format, const N: usize> Field<F, N> {
+    /// Create an empty field.
+    pub fn new(format: F) -> Self {
+        Self {
+            format,
+            enter: Key::Hash,
+            backspace: Key::Star,
+            clear: Key::Clear,
+            text: String::new(),
+            saved: String::new(),
+        }
+    }
+
+    /// Set the keys used to enter the value, remove the last character and
+    /// remove every character.
+    pub fn with_keys(mut self, enter: Key, backspace: Key, clear: Key) -> Self {
+        self.enter = enter;
+        self.backspace = backspace;
+        self.clear = clear;
+        self
+    }
+
+    /// Start with the given value, such as to edit an existing setting.
+    pub fn with_value(mut self, value: &F::Value) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Replace the text with the given value. If the value does not fit in
+    /// the field, or is not valid in its format, the field is cleared.
+    pub fn set_value(&mut self, value: &F::Value) {
+        self.text.clear();
+
+        if self.format.write(value, &mut self.text).is_err()
+            || self.format.parse(&self.text).is_none()
+        {
+            self.text.clear();
+        }
+
+        self.saved = self.text.clone();
+    }
+
+    /// Update the field with an event from the keypad. Only key presses are
+    /// used.
+    pub fn process(&mut self, event: KeyEvent<Key>) -> Option<FieldEvent<F::Value>> {
+        let KeyEvent::Pressed(key) = event else {
+            return None;
+        };
+
+        if key == self.enter {
+            return Some(match self.value() {
+                Some(value) => {
+                    self.saved = self.text.clone();
+                    FieldEvent::Entered(value)
+                }
+                None => FieldEvent::Invalid,
+            });
+        }
+
+        if key == self.clear {
+            self.text.clear();
+            return Some(FieldEvent::Changed);
+        }
+
+        if key == self.backspace {
+            if self.text.pop().is_none() {
+                return Some(FieldEvent::Rejected);
+            }
+
+            if F::SEPARATOR.is_some() && self.text.chars().last() == F::SEPARATOR {
+                self.text.pop();
+            }
+
+            return Some(FieldEvent::Changed);
+        }
+
+        let mut text = self.text.clone();
+
+        if self.format.input(&mut text, key) {
+            self.text = text;
+            Some(FieldEvent::Changed)
+        } else {
+            Some(FieldEvent::Rejected)
+        }
+    }
+
+    /// The text entered so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The value of the text entered so far, if it is complete and valid.
+    pub fn value(&self) -> Option<F::Value> {
+        self.format.parse(&self.text)
+    }
+
+    /// The format of the field.
+    pub fn format(&self) -> &F {
+        &self.format
+    }
+
+    /// Remove every character.
+    pub fn clear(&mut self) {
+        self.text.clear();
+    }
+
+    /// Discard any changes, restoring the text of the value last entered or
+    /// set.
+    pub fn revert(&mut self) {
+        self.text = self.saved.clone();
+    }
+}
+
+/// A number with a fixed number of decimal places, stored as an integer
+/// scaled by `10^places`.
+#[derive(Debug, Clone, Copy)]
+struct Number {
+    min: i32,
+    max: i32,
+    places: u8,
+    point: Option<Key>,
+    sign: Option<Key>,
+}
+
+impl Number {
+    /// Convert text to a scaled value, treating missing decimal places as
+    /// zeros.
+    fn scaled(&self, text: &str) -> Option<i64> {
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+
+        let (int, frac) = text.split_once('.').unwrap_or((text, ""));
+
+        if int.is_empty() || frac.len() > self.places as usize {
+            return None;
+        }
+
+        let mut value: i64 = 0;
+
+        for ch in int.chars().chain(frac.chars()) {
+            let digit = ch.to_digit(10)?;
+            value = value.checked_mul(10)?.checked_add(digit as i64)?;
+        }
+
+        let value =
+            value.checked_mul(10i64.checked_pow((self.places as usize - frac.len()) as u32)?)?;
+
+        Some(if negative { -value } else { value })
+    }
+
+    /// Determines whether more digits could make the text valid. Only the
+    /// bound furthest from zero is checked, because more digits can still
+    /// move the value past the other.
+    fn is_valid_prefix(&self, text: &str) -> bool {
+        match self.scaled(text) {
+            Some(value) if text.starts_with('-') => value >= self.min as i64,
+            Some(value) => value <= self.max as i64,
+            None => false,
+        }
+    }
+
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool {
+        if let Some(digit) = key.digit() {
+            if matches!(text.as_str(), "0" | "-0") {
+                text.pop();
+            }
+
+            return text.push((b'0' + digit) as char).is_ok() && self.is_valid_prefix(text);
+        }
+
+        if Some(key) == self.point {
+            if self.places == 0 || text.contains('.') {
+                return false;
+            }
+
+            if matches!(text.as_str(), "" | "-") && text.push('0').is_err() {
+                return false;
+            }
+
+            return text.push('.').is_ok();
+        }
+
+        if Some(key) == self.sign && self.min < 0 {
+            let mut signed: String<N> = String::new();
+            let pushed = match text.strip_prefix('-') {
+                Some(unsigned) => signed.push_str(unsigned),
+                None => signed.push('-').and_then(|_| signed.push_str(text)),
+            };
+
+            if pushed.is_err() || (signed.len() > 1 && !self.is_valid_prefix(&signed)) {
+                return false;
+            }
+
+            *text = signed;
+            return true;
+        }
+
+        false
+    }
+
+    fn parse(&self, text: &str) -> Option<i32> {
+        let value = self.scaled(text)?;
+
+        if value < self.min as i64 || value > self.max as i64 {
+            return None;
+        }
+
+        Some(value as i32)
+    }
+
+    fn write<W: Write>(&self, value: i32, text: &mut W) -> fmt::Result {
+        if self.places == 0 {
+            return write!(text, "{}", value);
+        }
+
+        let scale = 10u32.pow(self.places as u32);
+        let sign = if value < 0 { "-" } else { "" };
+        let abs = value.unsigned_abs();
+
+        write!(
+            text,
+            "{}{}.{:0width$}",
+            sign,
+            abs / scale,
+            abs % scale,
+            width = self.places as usize
+        )
+    }
+}
+
+/// A whole number between `min` and `max`, inclusive. Negative numbers can be
+/// entered once a sign key is set with [Integer::with_sign].
+#[derive(Debug, Clone, Copy)]
+pub struct Integer(Number);
+
+impl Integer {
+    /// Accept numbers from `min` to `max`.
+    ///
+    /// # Panics
+    ///
+    /// If `min` is greater than `max`.
+    pub fn new(min: i32, max: i32) -> Self {
+        assert!(min <= max, "min must not be greater than max");
+
+        Self(Number {
+            min,
+            max,
+            places: 0,
+            point: None,
+            sign: None,
+        })
+    }
+
+    /// Switch between positive and negative when `key` is pressed.
+    pub fn with_sign(mut self, key: Key) -> Self {
+        self.0.sign = Some(key);
+        self
+    }
+}
+
+impl Format for Integer {
+    type Value = i32;
+
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool {
+        self.0.input(text, key)
+    }
+
+    fn parse(&self, text: &str) -> Option<i32> {
+        self.0.parse(text)
+    }
+
+    fn write<W: Write>(&self, value: &i32, text: &mut W) -> fmt::Result {
+        self.0.write(*value, text)
+    }
+}
+
+/// A fixed-point number with up to `places` decimal places. The value is an
+/// integer scaled by `10^places`, so with two places `12.5` is `1250`, and
+/// `min` and `max` are given in the same units.
+///
+/// By default, [Key::Point] enters the decimal point.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal(Number);
+
+impl Decimal {
+    /// Accept numbers with up to `places` decimal places, from `min` to `max`
+    /// after scaling.
+    ///
+    /// # Panics
+    ///
+    /// If `places` is more than 9, or `min` is greater than `max`.
+    pub fn new(places: u8, min: i32, max: i32) -> Self {
+        assert!(places <= 9, "at most 9 decimal places are supported");
+        assert!(min <= max, "min must not be greater than max");
+
+        Self(Number {
+            min,
+            max,
+            places,
+            point: Some(Key::Point),
+            sign: None,
+        })
+    }
+
+    /// Enter the decimal point when `key` is pressed.
+    pub fn with_point(mut self, key: Key) -> Self {
+        self.0.point = Some(key);
+        self
+    }
+
+    /// Switch between positive and negative when `key` is pressed.
+    pub fn with_sign(mut self, key: Key) -> Self {
+        self.0.sign = Some(key);
+        self
+    }
+}
+
+impl Format for Decimal {
+    type Value = i32;
+
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool {
+        self.0.input(text, key)
+    }
+
+    fn parse(&self, text: &str) -> Option<i32> {
+        self.0.parse(text)
+    }
+
+    fn write<W: Write>(&self, value: &i32, text: &mut W) -> fmt::Result {
+        self.0.write(*value, text)
+    }
+}
+
+/// Up to `B` bytes entered as hexadecimal digits. The digits are right
+/// aligned, so `F` and `0F` both enter `0x0F`.
+///
+/// By default, the digit keys and [Key::A] to [Key::D] enter their own
+/// values. Keypads with keys for `E` and `F` can map them with
+/// [Hex::with_digits].
+#[derive(Debug, Clone, Copy)]
+pub struct Hex<const B: usize> {
+    digits: fn(Key) -> Option<u8>,
+}
+
+impl<const B: usize> Hex<B> {
+    /// Accept up to `2 * B` hexadecimal digits.
+    pub fn new() -> Self {
+        Self {
+            digits: |key| key.to_char()?.to_digit(16).map(|digit| digit as u8),
+        }
+    }
+
+    /// Set the value of the digit entered by each key, or [None] if the key
+    /// does not enter a digit.
+    pub fn with_digits(mut self, digits: fn(Key) -> Option<u8>) -> Self {
+        self.digits = digits;
+        self
+    }
+}
+
+impl<const B: usize> Default for Hex<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const B: usize> Format for Hex<B> {
+    type Value = [u8; B];
+
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool {
+        let Some(ch) = (self.digits)(key).and_then(|digit| char::from_digit(digit as u32, 16))
+        else {
+            return false;
+        };
+
+        text.len() < 2 * B && text.push(ch.to_ascii_uppercase()).is_ok()
+    }
+
+    fn parse(&self, text: &str) -> Option<[u8; B]> {
+        if text.is_empty() || text.len() > 2 * B {
+            return None;
+        }
+
+        let mut bytes = [0; B];
+
+        for (i, ch) in text.chars().rev().enumerate() {
+            bytes[B - 1 - i / 2] |= (ch.to_digit(16)? as u8) << (4 * (i % 2));
+        }
+
+        Some(bytes)
+    }
+
+    fn write<W: Write>(&self, value: &[u8; B], text: &mut W) -> fmt::Result {
+        value
+            .iter()
+            .try_for_each(|byte| write!(text, "{:02X}", byte))
+    }
+}
+
+/// Push a digit to text made of fixed-size groups of digits, inserting the
+/// separator at the start of each group after the first. Returns the digits
+/// entered, or [None] if every group is full.
+fn push_grouped<const N: usize>(
+    text: &mut String<N>,
+    digit: u8,
+    groups: &[usize],
+    separator: char,
+) -> Option<([u8; 8], usize)> {
+    let total = groups.iter().sum();
+    let mut digits = [0; 8];
+    let mut len = 0;
+
+    for ch in text.chars().filter_map(|ch| ch.to_digit(10)) {
+        if len == total {
+            return None;
+        }
+
+        digits[len] = ch as u8;
+        len += 1;
+    }
+
+    if len == total {
+        return None;
+    }
+
+    let mut boundary = 0;
+
+    for &group in groups {
+        if len == boundary && len > 0 {
+            text.push(separator).ok()?;
+        }
+
+        boundary += group;
+    }
+
+    text.push((b'0' + digit) as char).ok()?;
+    digits[len] = digit;
+
+    Some((digits, len + 1))
+}
+
+/// Read the digits of complete text made of groups of digits.
+fn digits<const D: usize>(text: &str) -> Option<[u8; D]> {
+    let mut digits = [0; D];
+    let mut len = 0;
+
+    for digit in text.chars().filter_map(|ch| ch.to_digit(10)) {
+        *digits.get_mut(len)? = digit as u8;
+        len += 1;
+    }
+
+    (len == D).then_some(digits)
+}
+
+/// A time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+/// A time of day on a 24 hour clock, entered as `HH:MM`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TimeOfDay;
+
+impl Format for TimeOfDay {
+    type Value = Time;
+
+    const SEPARATOR: Option<char> = Some(':');
+
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool {
+        let Some(digit) = key.digit() else {
+            return false;
+        };
+
+        let Some((d, len)) = push_grouped(text, digit, &[2, 2], ':') else {
+            return false;
+        };
+
+        match len {
+            1 => d[0] <= 2,
+            2 => d[0] * 10 + d[1] <= 23,
+            3 => d[2] <= 5,
+            _ => true,
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<Time> {
+        let [h0, h1, m0, m1] = digits(text)?;
+        let time = Time {
+            hour: h0 * 10 + h1,
+            minute: m0 * 10 + m1,
+        };
+
+        (time.hour <= 23 && time.minute <= 59).then_some(time)
+    }
+
+    fn write<W: Write>(&self, value: &Time, text: &mut W) -> fmt::Result {
+        write!(text, "{:02}:{:02}", value.hour, value.minute)
+    }
+}
+
+/// A date in the Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// The number of days in the given month.
+    pub fn days_in_month(year: u16, month: u8) -> u8 {
+        match month {
+            4 | 6 | 9 | 11 => 30,
+            2 if year.is_multiple_of(4)
+                && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+            {
+                29
+            }
+            2 => 28,
+            _ => 31,
+        }
+    }
+}
+
+/// A date, entered as `YYYY-MM-DD`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CalendarDate;
+
+impl Format for CalendarDate {
+    type Value = Date;
+
+    const SEPARATOR: Option<char> = Some('-');
+
+    fn input<const N: usize>(&self, text: &mut String<N>, key: Key) -> bool {
+        let Some(digit) = key.digit() else {
+            return false;
+        };
+
+        let Some((d, len)) = push_grouped(text, digit, &[4, 2, 2], '-') else {
+            return false;
+        };
+
+        let year = d[..4].iter().fold(0, |year, &d| year * 10 + d as u16);
+        let month = d[4] * 10 + d[5];
+
+        match len {
+            5 => d[4] <= 1,
+            6 => (1..=12).contains(&month),
+            7 => d[6] * 10 <= Date::days_in_month(year, month),
+            8 => (1..=Date::days_in_month(year, month)).contains(&(d[6] * 10 + d[7])),
+            _ => true,
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<Date> {
+        let d: [u8; 8] = digits(text)?;
+        let date = Date {
+            year: d[..4].iter().fold(0, |year, &d| year * 10 + d as u16),
+            month: d[4] * 10 + d[5],
+            day: d[6] * 10 + d[7],
+        };
+
+        ((1..=12).contains(&date.month)
+            && (1..=Date::days_in_month(date.year, date.month)).contains(&date.day))
+        .then_some(date)
+    }
+
+    fn write<W: Write>(&self, value: &Date, text: &mut W) -> fmt::Result {
+        write!(
+            text,
+            "{:04}-{:02}-{:02}",
+            value.year, value.month, value.day
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::tap;
+    use Key::*;
+
+    #[test]
+    fn integer() {
+        let mut field: Field<_, 4> = Field::new(Integer::new(0, 100));
+
+        assert_eq!(tap!(field, &[Num1, Num5]), Some(FieldEvent::Changed));
+        assert_eq!(tap!(field, &[Num0]), Some(FieldEvent::Rejected));
+        assert_eq!(field.text(), "15");
+        assert_eq!(tap!(field, &[Hash]), Some(FieldEvent::Entered(15)));
+
+        assert_eq!(tap!(field, &[Star, Star]), Some(FieldEvent::Changed));
+        assert_eq!(tap!(field, &[Star]), Some(FieldEvent::Rejected));
+        assert_eq!(tap!(field, &[Hash]), Some(FieldEvent::Invalid));
+
+        assert_eq!(tap!(field, &[Num0, Num7]), Some(FieldEvent::Changed));
+        assert_eq!(field.text(), "7");
+
+        tap!(field, &[Clear]);
+        assert_eq!(field.text(), "");
+    }
+
+    #[test]
+    fn negative_integer() {
+        let mut field: Field<_, 4> = Field::new(Integer::new(-50, 50).with_sign(A));
+
+        assert_eq!(tap!(field, &[Num5, A]), Some(FieldEvent::Changed));
+        assert_eq!(field.text(), "-5");
+        assert_eq!(tap!(field, &[Num1]), Some(FieldEvent::Rejected));
+        assert_eq!(tap!(field, &[Num0, Hash]), Some(FieldEvent::Entered(-50)));
+    }
+
+    #[test]
+    fn decimal() {
+        let mut field: Field<_, 8> = Field::new(Decimal::new(2, 0, 1000));
+
+        tap!(field, &[Point, Num5, Num0]);
+        assert_eq!(field.text(), "0.50");
+        assert_eq!(tap!(field, &[Num1]), Some(FieldEvent::Rejected));
+        assert_eq!(tap!(field, &[Point]), Some(FieldEvent::Rejected));
+        assert_eq!(tap!(field, &[Hash]), Some(FieldEvent::Entered(50)));
+
+        field.set_value(&1000);
+        assert_eq!(field.text(), "10.00");
+    }
+
+    #[test]
+    #[should_panic(expected = "min must not be greater than max")]
+    fn decimal_range_is_checked() {
+        Decimal::new(2, 1000, 0);
+    }
+
+    #[test]
+    fn hex() {
+        let mut field: Field<_, 4> = Field::new(Hex::<2>::new());
+
+        tap!(field, &[A, Num1, B]);
+        assert_eq!(field.text(), "A1B");
+        assert_eq!(
+            tap!(field, &[Hash]),
+            Some(FieldEvent::Entered([0x0A, 0x1B]))
+        );
+    }
+
+    #[test]
+    fn time_of_day() {
+        let mut field: Field<_, 8> = Field::new(TimeOfDay);
+
+        assert_eq!(tap!(field, &[Num2, Num4]), Some(FieldEvent::Rejected));
+        tap!(field, &[Num3, Num6]);
+        assert_eq!(field.text(), "23");
+        tap!(field, &[Num5, Num9]);
+        assert_eq!(field.text(), "23:59");
+        assert_eq!(tap!(field, &[Num0]), Some(FieldEvent::Rejected));
+
+        // The separator is removed with the digit after it.
+        tap!(field, &[Star, Star]);
+        assert_eq!(field.text(), "23");
+    }
+
+    #[test]
+    fn calendar_date() {
+        let mut field: Field<_, 16> = Field::new(CalendarDate);
+
+        tap!(field, &[Num2, Num0, Num2, Num4, Num0, Num2]);
+        assert_eq!(field.text(), "2024-02");
+        assert_eq!(tap!(field, &[Num3]), Some(FieldEvent::Rejected));
+        tap!(field, &[Num2, Num9]);
+        assert_eq!(
+            tap!(field, &[Hash]),
+            Some(FieldEvent::Entered(Date {
+                year: 2024,
+                month: 2,
+                day: 29
+            }))
+        );
+
+        tap!(field, &[Star, Star, Star, Num3]);
+        assert_eq!(field.text(), "2024-03");
+    }
+
+    #[test]
+    fn invalid_values_are_not_set() {
+        let date = Date {
+            year: 10000,
+            month: 1,
+            day: 1,
+        };
+        let mut field: Field<_, 16> = Field::new(CalendarDate).with_value(&date);
+
+        assert_eq!(field.text(), "");
+        assert_eq!(tap!(field, &[Num1]), Some(FieldEvent::Changed));
+
+        // Too many digits are rejected rather than overflowing.
+        let mut field: Field<_, 16> = Field::new(CalendarDate);
+        field.text = String::try_from("99999-99-99").unwrap();
+        assert_eq!(tap!(field, &[Num1]), Some(FieldEvent::Rejected));
+
+        let field: Field<_, 4> = Field::new(Integer::new(0, 100)).with_value(&500);
+        assert_eq!(field.text(), "");
+    }
+
+    #[test]
+    fn revert() {
+        let mut field: Field<_, 4> = Field::new(Integer::new(0, 100)).with_value(&50);
+
+        tap!(field, &[Clear, Num7]);
+        field.revert();
+        assert_eq!(field.value(), Some(50));
+
+        tap!(field, &[Clear, Num7, Hash, Num0]);
+        field.revert();
+        assert_eq!(field.value(), Some(7));
+    }
+}
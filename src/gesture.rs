@@ -0,0 +1,361 @@
+use crate::{Clock, EventQueue, KeyEvent, Overflow};
+
+/// A gesture made with a single key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture<K = u8> {
+    /// The key was pressed and released once.
+    Tap(K),
+    /// The key was tapped twice in quick succession.
+    DoubleTap(K),
+    /// The key has been held for longer than the long press threshold. This
+    /// is reported while the key is still held.
+    LongPress(K),
+    /// The key was released after a long press, having been held for the
+    /// given number of ticks.
+    Held(K, u32),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KeyState<K> {
+    key: K,
+    pressed_at: u32,
+    released_at: Option<u32>,
+    long_press: bool,
+    second_tap: bool,
+}
+
+/// Recognises [Gesture]s from the [KeyEvent]s of a keypad, timed using a
+/// [Clock]. Up to `N` keys are tracked at once, from when they are pressed
+/// until their gesture is reported, and a key pressed while `N` others are
+/// tracked is ignored. Up to `N` gestures are queued.
+///
+/// A tap is only reported once the double tap window has passed without a
+/// second tap, so setting the window to zero reports taps immediately and
+/// disables double taps.
+///
+/// # Examples
+///
+/// ```ignore
+/// // Long press after 800ms, double tap within 250ms.
+/// let mut gestures: Gestures<_, 4> = Gestures::new(|| timer.millis(), 800, 250);
+///
+/// loop {
+///     events.poll()?;
+///
+///     while let Some(event) = events.next_event() {
+///         gestures.process(event);
+///     }
+///
+///     gestures.poll();
+///
+///     while let Some(gesture) = gestures.next_gesture() {
+///         match gesture {
+///             Gesture::Tap(0xB) => println!("Hash tapped."),
+///             Gesture::LongPress(0xB) => println!("Hash held."),
+///             Gesture::DoubleTap(0xA) => println!("Star double tapped."),
+///             _ => (),
+///         }
+///     }
+/// }
+/// ```
+pub struct Gestures<C: Clock, const N: usize, K: Copy = u8> {
+    clock: C,
+    long_press: u32,
+    double_tap: u32,
+    keys: [Option<KeyState<K>>; N],
+    queue: EventQueue<Gesture<K>, N>,
+}
+
+impl<C: Clock, const N: usize, K: Copy + PartialEq> Gestures<C, N, K> {
+    /// Create a recogniser which reports a long press once a key is held for
+    /// `long_press` ticks, and a double tap when a key is pressed again within
+    /// `double_tap` ticks of being released.
+    pub fn new(clock: C, long_press: u32, double_tap: u32) -> Self {
+        Self {
+            clock,
+            long_press,
+            double_tap,
+            keys: [None; N],
+            queue: EventQueue::new(Overflow::DropOldest),
+        }
+    }
+
+    /// Update the gestures in progress with an event from the keypad.
+    pub fn process(&mut self, event: KeyEvent<K>) {
+        let now = self.clock.now();
+
+        match event {
+            KeyEvent::Pressed(key) => {
+                let slot = self
+                    .position(key)
+                    .or_else(|| self.keys.iter().position(Option::is_none));
+
+                if let Some(slot) = slot {
+                    let released_at = self.keys[slot].and_then(|state| state.released_at);
+                    let second_tap =
+                        released_at.is_some_and(|at| now.wrapping_sub(at) <= self.double_tap);
+
+                    if released_at.is_some() && !second_tap {
+                        self.queue.push(Gesture::Tap(key));
+                    }
+
+                    self.keys[slot] = Some(KeyState {
+                        key,
+                        pressed_at: now,
+                        released_at: None,
+                        long_press: false,
+                        second_tap,
+                    });
+                }
+            }
+            KeyEvent::Released(key) => {
+                let Some(slot) = self.position(key) else {
+                    return;
+                };
+
+                let Some(state) = self.keys[slot].as_mut() else {
+                    return;
+                };
+
+                if state.long_press {
+                    let held = now.wrapping_sub(state.pressed_at);
+                    self.queue.push(Gesture::Held(key, held));
+                    self.keys[slot] = None;
+                } else if state.second_tap {
+                    self.queue.push(Gesture::DoubleTap(key));
+                    self.keys[slot] = None;
+                } else if self.double_tap == 0 {
+                    self.queue.push(Gesture::Tap(key));
+                    self.keys[slot] = None;
+                } else {
+                    state.released_at = Some(now);
+                }
+            }
+            KeyEvent::Repeated(_) => (),
+        }
+    }
+
+    /// Check for gestures which depend only on the passage of time: long
+    /// presses, and taps which can no longer become double taps. This should
+    /// be called regularly, even when there are no new events.
+    pub fn poll(&mut self) {
+        let now = self.clock.now();
+
+        for slot in self.keys.iter_mut() {
+            let Some(state) = slot else {
+                continue;
+            };
+
+            match state.released_at {
+                Some(at) if now.wrapping_sub(at) > self.double_tap => {
+                    self.queue.push(Gesture::Tap(state.key));
+                    *slot = None;
+                }
+                None if !state.long_press
+                    && now.wrapping_sub(state.pressed_at) >= self.long_press =>
+                {
+                    if state.second_tap {
+                        self.queue.push(Gesture::Tap(state.key));
+                        state.second_tap = false;
+                    }
+
+                    self.queue.push(Gesture::LongPress(state.key));
+                    state.long_press = true;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Remove the oldest gesture from the queue.
+    pub fn next_gesture(&mut self) -> Option<Gesture<K>> {
+        self.queue.pop()
+    }
+
+    fn position(&self, key: K) -> Option<usize> {
+        self.keys
+            .iter()
+            .position(|state| state.is_some_and(|state| state.key == key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::testing::drain;
+
+    #[test]
+    fn tap() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 200);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(50);
+        recogniser.process(KeyEvent::Released(1));
+        now.set(250);
+        recogniser.poll();
+
+        // Not reported until the double tap window has passed.
+        assert_eq!(recogniser.next_gesture(), None);
+
+        now.set(251);
+        recogniser.poll();
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [Some(Gesture::Tap(1)), None, None, None]
+        );
+    }
+
+    #[test]
+    fn tap_without_double_tap() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 0);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        recogniser.process(KeyEvent::Released(1));
+        recogniser.process(KeyEvent::Pressed(1));
+        recogniser.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [Some(Gesture::Tap(1)), Some(Gesture::Tap(1)), None, None]
+        );
+    }
+
+    #[test]
+    fn double_tap() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 200);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(50);
+        recogniser.process(KeyEvent::Released(1));
+        now.set(250);
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(300);
+        recogniser.process(KeyEvent::Released(1));
+        now.set(1000);
+        recogniser.poll();
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [Some(Gesture::DoubleTap(1)), None, None, None]
+        );
+    }
+
+    #[test]
+    fn slow_taps_are_separate() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 200);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(50);
+        recogniser.process(KeyEvent::Released(1));
+        now.set(251);
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(300);
+        recogniser.process(KeyEvent::Released(1));
+        now.set(1000);
+        recogniser.poll();
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [Some(Gesture::Tap(1)), Some(Gesture::Tap(1)), None, None]
+        );
+    }
+
+    #[test]
+    fn long_press() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 200);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(499);
+        recogniser.poll();
+
+        assert_eq!(recogniser.next_gesture(), None);
+
+        now.set(500);
+        recogniser.poll();
+
+        assert_eq!(recogniser.next_gesture(), Some(Gesture::LongPress(1)));
+
+        now.set(800);
+        recogniser.poll();
+        recogniser.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [Some(Gesture::Held(1, 800)), None, None, None]
+        );
+    }
+
+    #[test]
+    fn tap_then_long_press() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 200);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(50);
+        recogniser.process(KeyEvent::Released(1));
+        now.set(100);
+        recogniser.process(KeyEvent::Pressed(1));
+        now.set(600);
+        recogniser.poll();
+        recogniser.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [
+                Some(Gesture::Tap(1)),
+                Some(Gesture::LongPress(1)),
+                Some(Gesture::Held(1, 500)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_are_independent() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 4> = Gestures::new(|| now.get(), 500, 0);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        recogniser.process(KeyEvent::Pressed(2));
+        recogniser.process(KeyEvent::Released(2));
+        now.set(500);
+        recogniser.poll();
+        recogniser.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [
+                Some(Gesture::Tap(2)),
+                Some(Gesture::LongPress(1)),
+                Some(Gesture::Held(1, 500)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_past_n_are_ignored() {
+        let now = Cell::new(0);
+        let mut recogniser: Gestures<_, 2> = Gestures::new(|| now.get(), 500, 0);
+
+        recogniser.process(KeyEvent::Pressed(1));
+        recogniser.process(KeyEvent::Pressed(2));
+        recogniser.process(KeyEvent::Pressed(3));
+        recogniser.process(KeyEvent::Released(3));
+        recogniser.process(KeyEvent::Released(2));
+        recogniser.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| recogniser.next_gesture()),
+            [Some(Gesture::Tap(2)), Some(Gesture::Tap(1)), None]
+        );
+    }
+}
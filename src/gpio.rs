@@ -0,0 +1,718 @@
+use crate::keymap::HEX_4X4;
+use crate::{ColumnPin, KeySet, Keypad, Keys, RowPin};
+
+#[cfg(feature = "async")]
+mod wait;
+
+/// An error which occurred while reading a [GpioKeypad], identifying the pin
+/// which failed by its index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<C, R> {
+    /// A column pin could not be set.
+    Column(usize, C),
+    /// A row pin could not be read.
+    Row(usize, R),
+}
+
+/// The logic level at which a pin is considered active.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    /// Active when high. Rows are pulled down, and columns are driven high to
+    /// be scanned.
+    #[default]
+    ActiveHigh,
+    /// Active when low. Rows are pulled up, and columns are driven low to be
+    /// scanned.
+    ActiveLow,
+}
+
+impl Polarity {
+    pub(crate) fn is_active(self, high: bool) -> bool {
+        high == (self == Polarity::ActiveHigh)
+    }
+}
+
+/// A key which switches between the layers of a [GpioKeypad]'s keymap. Layer
+/// keys are not reported as keys themselves.
+///
+/// When more than one layer is active, keys are taken from the highest. The
+/// layer a key was pressed on is remembered until it is released, so the
+/// same key is reported for the whole press even if the layer changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKey {
+    /// Activate the layer while the key is held.
+    Momentary(usize),
+    /// Activate or deactivate the layer each time the key is pressed. Each
+    /// press read changes the layer, so on keypads without hardware
+    /// debouncing, set [GpioKeypad::with_layer_debounce] to cover the bounce.
+    Toggle(usize),
+}
+
+/// How a [GpioKeypad] handles ghosting, where three keys pressed at the corners
+/// of a rectangle make the key at the fourth corner appear pressed. Ghosting
+/// only occurs on keypads without a diode for each key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GhostPolicy {
+    /// Report the keys as scanned, without checking for ghosting.
+    #[default]
+    Ignore,
+    /// Report the keys as scanned, but flag scans which may contain ghost keys
+    /// (see [GpioKeypad::is_ambiguous]).
+    Flag,
+    /// Flag scans which may contain ghost keys, and remove every key which may
+    /// be a ghost from the result.
+    Suppress,
+}
+
+/// A keypad implemented as a matrix of `COLS` output pins (columns) and `ROWS`
+/// input pins (rows).
+///
+/// All of the column pins must share a type, as must all of the row pins. Most
+/// HALs provide a way to erase or degrade a pin to a common type for this. See
+/// [ColumnPin] and [RowPin] for the pins which can be used.
+///
+/// Keys are reported as type `K`, which is any `Copy` type used in the keymap.
+/// The keymap can have up to 32 `LAYERS`, which are switched between using
+/// [LayerKey]s.
+///
+/// # Examples
+///
+/// ```ignore
+/// // A 3x4 phone keypad.
+/// let keypad = GpioKeypad::new([c1, c2, c3], [r1, r2, r3, r4]).with_keymap([
+///     ['1', '2', '3'],
+///     ['4', '5', '6'],
+///     ['7', '8', '9'],
+///     ['*', '0', '#'],
+/// ]);
+/// ```
+pub struct GpioKeypad<C, R, const COLS: usize, const ROWS: usize, K = u8, const LAYERS: usize = 1>
+where
+    C: ColumnPin,
+    R: RowPin,
+{
+    cols: [C; COLS],
+    rows: [R; ROWS],
+    layers: [[[K; COLS]; ROWS]; LAYERS],
+    layer_keys: [[Option<LayerKey>; COLS]; ROWS],
+    layer_key_set: KeySet,
+    layer_debounce: u8,
+    layer_pending: KeySet,
+    layer_reads: u8,
+    layer_held: KeySet,
+    toggled: u32,
+    held: KeySet,
+    pressed_on: [[u8; COLS]; ROWS],
+    col_polarity: Polarity,
+    row_polarity: Polarity,
+    ghost_policy: GhostPolicy,
+    ambiguous: bool,
+    idle: bool,
+}
+
+impl<C, R, const COLS: usize, const ROWS: usize> GpioKeypad<C, R, COLS, ROWS>
+where
+    C: ColumnPin,
+    R: RowPin,
+{
+    /// Create a keypad from its column and row pins. A 4x4 keypad uses the
+    /// hexadecimal [keymap::HEX_4X4](crate::keymap::HEX_4X4), as in earlier
+    /// versions of this crate. On keypads of other sizes, keys are numbered
+    /// from zero, left to right and then top to bottom. Either can be replaced
+    /// using [GpioKeypad::with_keymap].
+    ///
+    /// Keypads of up to eight rows by eight columns are supported. The pins
+    /// are not used until the keypad is first read.
+    pub fn new(cols: [C; COLS], rows: [R; ROWS]) -> Self {
+        const {
+            assert!(
+                COLS <= KeySet::MAX_COLS && ROWS <= KeySet::MAX_ROWS,
+                "keypads larger than 8x8 are not supported"
+            )
+        };
+
+        let keymap = core::array::from_fn(|row| {
+            core::array::from_fn(|col| match (COLS, ROWS) {
+                (4, 4) => HEX_4X4[row][col],
+                _ => (row * COLS + col) as u8,
+            })
+        });
+
+        Self {
+            cols,
+            rows,
+            layers: [keymap],
+            layer_keys: [[None; COLS]; ROWS],
+            layer_key_set: KeySet::new(),
+            layer_debounce: 1,
+            layer_pending: KeySet::new(),
+            layer_reads: 0,
+            layer_held: KeySet::new(),
+            toggled: 0,
+            held: KeySet::new(),
+            pressed_on: [[0; COLS]; ROWS],
+            col_polarity: Polarity::ActiveHigh,
+            row_polarity: Polarity::ActiveHigh,
+            ghost_policy: GhostPolicy::Ignore,
+            ambiguous: false,
+            idle: false,
+        }
+    }
+}
+
+impl<C, R, const COLS: usize, const ROWS: usize, K, const LAYERS: usize>
+    GpioKeypad<C, R, COLS, ROWS, K, LAYERS>
+where
+    C: ColumnPin,
+    R: RowPin,
+    K: Copy,
+{
+    /// Set the keymap, which gives the key at each position as `[row][col]`.
+    /// The keymap can be of any `Copy` type, which the keypad then reports.
+    /// Any [LayerKey]s are removed.
+    pub fn with_keymap<J: Copy>(
+        self,
+        keymap: [[J; COLS]; ROWS],
+    ) -> GpioKeypad<C, R, COLS, ROWS, J> {
+        self.with_layers([keymap])
+    }
+
+    /// Set a keymap with several layers, each of which gives the key at each
+    /// position as `[layer][row][col]`. Layer 0 is always active, and other
+    /// layers are activated by setting [LayerKey]s with
+    /// [GpioKeypad::with_layer_key]. Any [LayerKey]s set before the keymap
+    /// are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use embedded_keypad::Key::*;
+    ///
+    /// // Hold the bottom left key to use the arrows and function keys.
+    /// let keypad = GpioKeypad::new(cols, rows)
+    ///     .with_layers([
+    ///         [
+    ///             [Num1, Num2, Num3],
+    ///             [Num4, Num5, Num6],
+    ///             [Num7, Num8, Num9],
+    ///             [Star, Num0, Hash],
+    ///         ],
+    ///         [
+    ///             [F1, Up, F2],
+    ///             [Left, Enter, Right],
+    ///             [F3, Down, F4],
+    ///             [Star, Clear, Hash],
+    ///         ],
+    ///     ])
+    ///     .with_layer_key(3, 0, LayerKey::Momentary(1));
+    /// ```
+    pub fn with_layers<J: Copy, const L: usize>(
+        self,
+        layers: [[[J; COLS]; ROWS]; L],
+    ) -> GpioKeypad<C, R, COLS, ROWS, J, L> {
+        const {
+            assert!(
+                L > 0 && L <= 32,
+                "keymaps must have between 1 and 32 layers"
+            )
+        };
+
+        GpioKeypad {
+            cols: self.cols,
+            rows: self.rows,
+            layers,
+            layer_keys: [[None; COLS]; ROWS],
+            layer_key_set: KeySet::new(),
+            layer_debounce: self.layer_debounce,
+            layer_pending: KeySet::new(),
+            layer_reads: 0,
+            layer_held: KeySet::new(),
+            toggled: 0,
+            held: KeySet::new(),
+            pressed_on: [[0; COLS]; ROWS],
+            col_polarity: self.col_polarity,
+            row_polarity: self.row_polarity,
+            ghost_policy: self.ghost_policy,
+            ambiguous: self.ambiguous,
+            idle: self.idle,
+        }
+    }
+
+    /// Make the key at the given position switch layers, rather than being
+    /// reported as a key.
+    ///
+    /// # Panics
+    ///
+    /// If the position or layer is out of range.
+    pub fn with_layer_key(mut self, row: usize, col: usize, layer_key: LayerKey) -> Self {
+        let (LayerKey::Momentary(layer) | LayerKey::Toggle(layer)) = layer_key;
+        assert!(layer < LAYERS, "layer out of range");
+
+        self.layer_keys[row][col] = Some(layer_key);
+        self.layer_key_set.insert(row, col);
+        self
+    }
+
+    /// Only change layers once the layer keys held have been the same for
+    /// `reads` reads in a row, so that contact bounce does not toggle a layer
+    /// more than once. By default, layers change on the first read.
+    pub fn with_layer_debounce(mut self, reads: u8) -> Self {
+        self.layer_debounce = reads.max(1);
+        self
+    }
+
+    /// The layer which keys pressed now are taken from.
+    pub fn active_layer(&self) -> usize {
+        let mut active = self.toggled | 1;
+
+        for (row, col) in self.layer_held.iter() {
+            if let Some(LayerKey::Momentary(layer)) = self.layer_keys[row][col] {
+                active |= 1 << layer;
+            }
+        }
+
+        31 - active.leading_zeros() as usize
+    }
+
+    /// Update the layers after a scan, recording which layer each newly
+    /// pressed key was pressed on.
+    fn update_layers(&mut self, keys: &KeySet) {
+        let layer_keys = keys.intersection(&self.layer_key_set);
+
+        if layer_keys != self.layer_pending {
+            self.layer_pending = layer_keys;
+            self.layer_reads = 0;
+        }
+
+        self.layer_reads = self.layer_reads.saturating_add(1);
+
+        if self.layer_reads >= self.layer_debounce && self.layer_pending != self.layer_held {
+            for (row, col) in self.layer_pending.difference(&self.layer_held) {
+                if let Some(LayerKey::Toggle(layer)) = self.layer_keys[row][col] {
+                    self.toggled ^= 1 << layer;
+                }
+            }
+
+            self.layer_held = self.layer_pending;
+        }
+
+        let pressed = keys.difference(&self.held);
+
+        self.held = *keys;
+        let layer = self.active_layer() as u8;
+
+        for (row, col) in pressed {
+            self.pressed_on[row][col] = layer;
+        }
+    }
+
+    /// Set the polarity of the column and row pins. By default, both are
+    /// [Polarity::ActiveHigh], which requires pull-down resistors on the rows.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Rows use internal pull-ups, and columns are driven low to scan.
+    /// let keypad = GpioKeypad::new(cols, rows)
+    ///     .with_polarity(Polarity::ActiveLow, Polarity::ActiveLow);
+    /// ```
+    pub fn with_polarity(mut self, cols: Polarity, rows: Polarity) -> Self {
+        self.col_polarity = cols;
+        self.row_polarity = rows;
+        self.idle = false;
+        self
+    }
+
+    /// Set how ghost keys are handled. By default, the [GhostPolicy] is
+    /// [GhostPolicy::Ignore], which is correct for keypads with a diode for
+    /// each key.
+    pub fn with_ghost_policy(mut self, ghost_policy: GhostPolicy) -> Self {
+        self.ghost_policy = ghost_policy;
+        self
+    }
+
+    /// Returns true if the most recent read may have contained ghost keys.
+    /// This is always false when the [GhostPolicy] is [GhostPolicy::Ignore].
+    pub fn is_ambiguous(&self) -> bool {
+        self.ambiguous
+    }
+
+    /// Drive every column active, so that any key press can be detected.
+    fn reset(&mut self) -> Result<(), Error<C::Error, R::Error>> {
+        let active_high = self.col_polarity.is_active(true);
+
+        for (col, pin) in self.cols.iter_mut().enumerate() {
+            Self::drive(pin, active_high).map_err(|err| Error::Column(col, err))?;
+        }
+
+        self.idle = true;
+        Ok(())
+    }
+
+    /// Drive only the column at `pos` active, so that its keys can be read.
+    fn select(&mut self, pos: usize) -> Result<(), Error<C::Error, R::Error>> {
+        let active_high = self.col_polarity.is_active(true);
+        self.idle = false;
+
+        for (col, pin) in self.cols.iter_mut().enumerate() {
+            Self::drive(pin, (col == pos) == active_high).map_err(|err| Error::Column(col, err))?;
+        }
+
+        Ok(())
+    }
+
+    fn drive(pin: &mut C, high: bool) -> Result<(), C::Error> {
+        if high {
+            pin.set_high()
+        } else {
+            pin.set_low()
+        }
+    }
+
+    fn row_is_active(&mut self, row: usize) -> Result<bool, Error<C::Error, R::Error>> {
+        self.rows[row]
+            .is_high()
+            .map(|high| self.row_polarity.is_active(high))
+            .map_err(|err| Error::Row(row, err))
+    }
+
+    fn any_row_is_active(&mut self) -> Result<bool, Error<C::Error, R::Error>> {
+        if !self.idle {
+            self.reset()?;
+        }
+
+        for row in 0..ROWS {
+            if self.row_is_active(row)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Read every key pressed on the keypad. Unlike [Keypad::read_multi],
+    /// this reports all of the keys which share a column. On keypads without
+    /// a diode for each key, some of the keys reported may be ghosts, which
+    /// can be detected by setting a [GhostPolicy].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let keys = keypad.read_set()?;
+    ///
+    /// for (row, col) in keys.iter() {
+    ///     println!("Key at row {}, column {} is pressed.", row, col);
+    /// }
+    /// ```
+    pub fn read_set(&mut self) -> Result<KeySet, Error<C::Error, R::Error>> {
+        let keys = self.scan()?;
+        self.update_layers(&keys);
+        Ok(keys)
+    }
+
+    fn scan(&mut self) -> Result<KeySet, Error<C::Error, R::Error>> {
+        self.ambiguous = false;
+
+        if !self.any_row_is_active()? {
+            return Ok(KeySet::new());
+        }
+
+        let mut keys = KeySet::new();
+
+        for col in 0..COLS {
+            self.select(col)?;
+
+            for row in 0..ROWS {
+                if self.row_is_active(row)? {
+                    keys.insert(row, col);
+                }
+            }
+        }
+
+        self.reset()?;
+
+        if self.ghost_policy == GhostPolicy::Ignore {
+            return Ok(keys);
+        }
+
+        let ghosts = keys.ghosts();
+        self.ambiguous = !ghosts.is_empty();
+
+        match self.ghost_policy {
+            GhostPolicy::Suppress => Ok(keys.difference(&ghosts)),
+            _ => Ok(keys),
+        }
+    }
+
+    /// Find the key pressed in a column, preferring the highest row. Layer
+    /// keys are ignored, and the key is taken from the layer it was pressed on.
+    fn read_key(&self, keys: &KeySet, col: usize) -> Option<K> {
+        (0..ROWS)
+            .rev()
+            .find(|&row| keys.contains(row, col) && !self.layer_key_set.contains(row, col))
+            .map(|row| self.layers[self.pressed_on[row][col] as usize][row][col])
+    }
+}
+
+impl<C, R, const COLS: usize, const ROWS: usize, K, const LAYERS: usize> Keypad
+    for GpioKeypad<C, R, COLS, ROWS, K, LAYERS>
+where
+    C: ColumnPin,
+    R: RowPin,
+    K: Copy + PartialEq,
+{
+    type Key = K;
+    type Error = Error<C::Error, R::Error>;
+
+    fn key_is_pressed(&mut self) -> Result<bool, Self::Error> {
+        self.any_row_is_active()
+    }
+
+    fn read(&mut self) -> Result<Option<K>, Self::Error> {
+        let keys = self.read_set()?;
+        Ok((0..COLS).find_map(|col| self.read_key(&keys, col)))
+    }
+
+    fn read_multi(&mut self) -> Result<Option<Keys<K>>, Self::Error> {
+        let keys = self.read_set()?;
+        let keys = (0..COLS).filter_map(|col| self.read_key(&keys, col));
+
+        Ok(Keys::collect(keys.take(4)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Matrix, PinError, Trace};
+
+    #[test]
+    fn read_prefers_highest_row() {
+        let matrix = Matrix::<4, 4>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        matrix.press(0, 1);
+        matrix.press(2, 1);
+        assert_eq!(keypad.read(), Ok(Some(0x8)));
+
+        matrix.press(3, 0);
+        assert_eq!(keypad.read(), Ok(Some(0xA)));
+    }
+
+    #[test]
+    fn read_multi_reports_one_key_per_column() {
+        let matrix = Matrix::<5, 2>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        for col in (0..5).rev() {
+            matrix.press(col % 2, col);
+        }
+
+        matrix.press(1, 0);
+        assert_eq!(keypad.read_multi(), Ok(Some(Keys::Four(5, 6, 2, 8))));
+        assert_eq!(keypad.read_set().map(|keys| keys.len()), Ok(6));
+    }
+
+    #[test]
+    fn key_is_pressed() {
+        let matrix = Matrix::<3, 4>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        assert_eq!(keypad.key_is_pressed(), Ok(false));
+
+        matrix.press(3, 2);
+        assert_eq!(keypad.key_is_pressed(), Ok(true));
+        assert_eq!(keypad.read(), Ok(Some(11)));
+        assert_eq!(keypad.key_is_pressed(), Ok(true));
+    }
+
+    #[test]
+    fn active_low() {
+        let matrix = Matrix::<3, 4>::new().with_polarity(Polarity::ActiveLow, Polarity::ActiveLow);
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows())
+            .with_polarity(Polarity::ActiveLow, Polarity::ActiveLow);
+
+        assert_eq!(keypad.read(), Ok(None));
+
+        matrix.press(1, 1);
+        assert_eq!(keypad.read(), Ok(Some(4)));
+    }
+
+    #[test]
+    fn ghosting() {
+        let matrix = Matrix::<4, 4>::new().without_diodes();
+        matrix.press(0, 0);
+        matrix.press(0, 2);
+        matrix.press(3, 0);
+
+        let ghosted: KeySet = [(0, 0), (0, 2), (3, 0), (3, 2)].into_iter().collect();
+
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+        assert_eq!(keypad.read_set(), Ok(ghosted));
+        assert!(!keypad.is_ambiguous());
+
+        let mut keypad = keypad.with_ghost_policy(GhostPolicy::Flag);
+        assert_eq!(keypad.read_set(), Ok(ghosted));
+        assert!(keypad.is_ambiguous());
+
+        let mut keypad = keypad.with_ghost_policy(GhostPolicy::Suppress);
+        assert_eq!(keypad.read_set(), Ok(KeySet::new()));
+        assert!(keypad.is_ambiguous());
+
+        matrix.release(0, 0);
+        assert_eq!(keypad.read_multi(), Ok(Some(Keys::Two(0xA, 0x3))));
+        assert!(!keypad.is_ambiguous());
+    }
+
+    #[test]
+    fn diodes_prevent_ghosting() {
+        let matrix = Matrix::<4, 4>::new();
+        matrix.press(0, 0);
+        matrix.press(0, 2);
+        matrix.press(3, 0);
+
+        let mut keypad =
+            GpioKeypad::new(matrix.columns(), matrix.rows()).with_ghost_policy(GhostPolicy::Flag);
+
+        assert_eq!(keypad.read_set().map(|keys| keys.len()), Ok(3));
+        assert!(!keypad.is_ambiguous());
+    }
+
+    #[test]
+    fn errors_identify_pin() {
+        let matrix = Matrix::<3, 4>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        matrix.fail_row(2, true);
+        assert_eq!(keypad.key_is_pressed(), Err(Error::Row(2, PinError)));
+
+        matrix.fail_row(2, false);
+        matrix.fail_column(1, true);
+        matrix.press(0, 0);
+        assert_eq!(keypad.read(), Err(Error::Column(1, PinError)));
+    }
+
+    #[test]
+    fn layer_is_kept_until_release() {
+        let matrix = Matrix::<2, 1>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows())
+            .with_layers([[['a', 'b']], [['A', 'B']]])
+            .with_layer_key(0, 0, LayerKey::Momentary(1));
+
+        matrix.press(0, 1);
+        assert_eq!(keypad.read(), Ok(Some('b')));
+
+        matrix.release(0, 1);
+        matrix.press(0, 0);
+        assert_eq!(keypad.read(), Ok(None));
+        assert_eq!(keypad.active_layer(), 1);
+
+        matrix.press(0, 1);
+        assert_eq!(keypad.read(), Ok(Some('B')));
+
+        matrix.release(0, 0);
+        assert_eq!(keypad.read(), Ok(Some('B')));
+        assert_eq!(keypad.active_layer(), 0);
+    }
+
+    #[test]
+    fn layer_keys_are_removed_with_keymap() {
+        let matrix = Matrix::<2, 1>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows())
+            .with_layers([[['a', 'b']], [['c', 'd']], [['e', 'f']]])
+            .with_layer_key(0, 0, LayerKey::Momentary(2))
+            .with_keymap([['A', 'B']]);
+
+        matrix.press(0, 0);
+        matrix.press(0, 1);
+        assert_eq!(keypad.read(), Ok(Some('A')));
+        assert_eq!(keypad.active_layer(), 0);
+    }
+
+    #[test]
+    fn bouncing_toggle_key() {
+        let read_after_bounce = |debounce| {
+            let matrix = Matrix::<2, 2>::new();
+            let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows())
+                .with_layers([[['a', 'b'], ['c', 'd']], [['A', 'B'], ['C', 'D']]])
+                .with_layer_key(1, 0, LayerKey::Toggle(1))
+                .with_layer_debounce(debounce);
+
+            // Holding a key in the first row means that every read scans
+            // the keypad, and sees the toggle key bounce.
+            matrix.press(0, 1);
+            matrix.press_bouncing(1, 0, 2);
+
+            for _ in 0..4 {
+                keypad.read_set().unwrap();
+            }
+
+            matrix.release_all();
+            keypad.read_set().unwrap();
+            matrix.press(0, 1);
+            keypad.read()
+        };
+
+        // The bounce toggles the layer twice more, back to the first layer.
+        assert_eq!(read_after_bounce(1), Ok(Some('b')));
+        assert_eq!(read_after_bounce(2), Ok(Some('B')));
+    }
+
+    #[test]
+    fn scan_sequence() {
+        let matrix = Matrix::<2, 2>::new();
+        let trace = Trace::<32>::new();
+        let mut keypad =
+            GpioKeypad::new(trace.columns(matrix.columns()), trace.rows(matrix.rows()));
+
+        matrix.press(1, 0);
+        assert_eq!(keypad.read(), Ok(Some(2)));
+        assert_eq!(
+            std::format!("{}", trace),
+            "C0=1 C1=1 R0=0 R1=1 \
+             C0=1 C1=0 R0=0 R1=1 \
+             C0=0 C1=1 R0=0 R1=0 \
+             C0=1 C1=1"
+        );
+
+        // Every column is left active, so the next read only reads the rows.
+        trace.clear();
+        matrix.release(1, 0);
+        assert_eq!(keypad.read(), Ok(None));
+        assert_eq!(std::format!("{}", trace), "R0=0 R1=0");
+    }
+
+    #[test]
+    fn active_low_scan_sequence() {
+        let matrix = Matrix::<2, 2>::new().with_polarity(Polarity::ActiveLow, Polarity::ActiveLow);
+        let trace = Trace::<32>::new();
+        let mut keypad =
+            GpioKeypad::new(trace.columns(matrix.columns()), trace.rows(matrix.rows()))
+                .with_polarity(Polarity::ActiveLow, Polarity::ActiveLow);
+
+        matrix.press(0, 1);
+        assert_eq!(keypad.read(), Ok(Some(1)));
+        assert_eq!(
+            std::format!("{}", trace),
+            "C0=0 C1=0 R0=0 \
+             C0=0 C1=1 R0=1 R1=1 \
+             C0=1 C1=0 R0=0 R1=1 \
+             C0=0 C1=0"
+        );
+    }
+
+    #[test]
+    fn default_keymaps() {
+        let matrix = Matrix::<4, 4>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        matrix.press(0, 3);
+        assert_eq!(keypad.read(), Ok(Some(0xF)));
+
+        let matrix = Matrix::<3, 2>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+
+        matrix.press(1, 2);
+        assert_eq!(keypad.read(), Ok(Some(5)));
+    }
+}
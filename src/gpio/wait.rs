@@ -0,0 +1,142 @@
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::Poll;
+
+use embedded_hal_1::digital::InputPin;
+use embedded_hal_async::digital::Wait;
+
+use super::{Error, GpioKeypad, Polarity};
+use crate::pin::eh1;
+use crate::{ColumnPin, Keypad};
+
+impl<C, R, const COLS: usize, const ROWS: usize, K, const LAYERS: usize>
+    GpioKeypad<C, eh1::Pin<R>, COLS, ROWS, K, LAYERS>
+where
+    C: ColumnPin,
+    R: InputPin + Wait,
+    K: Copy + PartialEq,
+{
+    /// Wait for a key to be pressed, then read it from the keypad. Every
+    /// column is driven active while waiting, so that a press on any row can
+    /// be detected as an edge by the row pins. This allows the executor to
+    /// sleep until a key is pressed, rather than polling
+    /// [Keypad::key_is_pressed].
+    ///
+    /// Only new presses are detected, so a key which is already held when
+    /// this is called is not returned.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// loop {
+    ///     let key = keypad.wait_for_key().await?;
+    ///     println!("Got key: {}.", key);
+    /// }
+    /// ```
+    pub async fn wait_for_key(&mut self) -> Result<K, Error<C::Error, R::Error>> {
+        loop {
+            if !self.idle {
+                self.reset()?;
+            }
+
+            let polarity = self.row_polarity;
+            let mut index = 0;
+
+            let edges = self.rows.each_mut().map(|row| {
+                let row_index = index;
+                index += 1;
+
+                async move {
+                    match polarity {
+                        Polarity::ActiveHigh => row.0.wait_for_rising_edge().await,
+                        Polarity::ActiveLow => row.0.wait_for_falling_edge().await,
+                    }
+                    .map_err(|err| Error::Row(row_index, err))
+                }
+            });
+
+            select(edges).await?;
+
+            if let Some(key) = self.read()? {
+                return Ok(key);
+            }
+        }
+    }
+}
+
+/// Poll every future in the array until one of them completes, returning its
+/// output.
+async fn select<F: Future, const N: usize>(mut futures: [F; N]) -> F::Output {
+    poll_fn(|cx| {
+        for future in futures.iter_mut() {
+            // SAFETY: `futures` is part of the state of this async fn, which
+            // is pinned once it is first polled, and is only used through
+            // this closure, so none of the futures is moved after being
+            // pinned here.
+            let future = unsafe { Pin::new_unchecked(future) };
+
+            if let Poll::Ready(output) = future.poll(cx) {
+                return Poll::Ready(output);
+            }
+        }
+
+        Poll::Pending
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::pending;
+    use core::pin::pin;
+    use core::task::{Context, Waker};
+
+    use super::*;
+    use crate::mock::{Matrix, PinError};
+
+    /// Poll a future once.
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        future.poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn select_returns_first_ready() {
+        let futures = [0, 1, 2].map(|i| async move {
+            if i == 0 {
+                pending::<()>().await;
+            }
+
+            i
+        });
+
+        assert_eq!(poll_once(pin!(select(futures))), Poll::Ready(1));
+    }
+
+    #[test]
+    fn waits_for_key() {
+        let matrix = Matrix::<3, 2>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows().map(eh1::Pin));
+        let mut key = pin!(keypad.wait_for_key());
+
+        assert_eq!(poll_once(key.as_mut()), Poll::Pending);
+        assert_eq!(poll_once(key.as_mut()), Poll::Pending);
+
+        matrix.press(1, 2);
+        assert_eq!(poll_once(key.as_mut()), Poll::Ready(Ok(5)));
+    }
+
+    #[test]
+    fn row_error() {
+        let matrix = Matrix::<3, 2>::new();
+        let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows().map(eh1::Pin));
+        let mut key = pin!(keypad.wait_for_key());
+
+        assert_eq!(poll_once(key.as_mut()), Poll::Pending);
+
+        matrix.fail_row(1, true);
+        assert_eq!(
+            poll_once(key.as_mut()),
+            Poll::Ready(Err(Error::Row(1, PinError)))
+        );
+    }
+}
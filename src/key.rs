@@ -0,0 +1,205 @@
+use core::fmt;
+use core::str::FromStr;
+
+/// A key found on common keypads, for use in a keymap.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_keypad::Key;
+///
+/// assert_eq!(Key::Num7.to_char(), Some('7'));
+/// assert_eq!("#".parse(), Ok(Key::Hash));
+/// assert_eq!("enter".parse(), Ok(Key::Enter));
+/// assert_eq!(Key::F2.to_string(), "F2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Key {
+    Num0,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Star,
+    Hash,
+    A,
+    B,
+    C,
+    D,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Equals,
+    Point,
+    Enter,
+    Clear,
+    Up,
+    Down,
+    Left,
+    Right,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+}
+
+impl Key {
+    /// Every key, in the order they are declared.
+    pub const ALL: [Key; 34] = {
+        use Key::*;
+
+        [
+            Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9, Star, Hash, A, B, C, D,
+            Plus, Minus, Multiply, Divide, Equals, Point, Enter, Clear, Up, Down, Left, Right, F1,
+            F2, F3, F4, F5, F6,
+        ]
+    };
+
+    /// The key for a decimal digit, or [None] if `digit` is greater than 9.
+    pub const fn from_digit(digit: u8) -> Option<Key> {
+        use Key::*;
+
+        match digit {
+            0 => Some(Num0),
+            1 => Some(Num1),
+            2 => Some(Num2),
+            3 => Some(Num3),
+            4 => Some(Num4),
+            5 => Some(Num5),
+            6 => Some(Num6),
+            7 => Some(Num7),
+            8 => Some(Num8),
+            9 => Some(Num9),
+            _ => None,
+        }
+    }
+
+    /// The decimal digit of a number key.
+    pub const fn digit(self) -> Option<u8> {
+        use Key::*;
+
+        match self {
+            Num0 => Some(0),
+            Num1 => Some(1),
+            Num2 => Some(2),
+            Num3 => Some(3),
+            Num4 => Some(4),
+            Num5 => Some(5),
+            Num6 => Some(6),
+            Num7 => Some(7),
+            Num8 => Some(8),
+            Num9 => Some(9),
+            _ => None,
+        }
+    }
+
+    /// The character printed on the key, for keys which are labelled with a
+    /// single character.
+    pub const fn to_char(self) -> Option<char> {
+        use Key::*;
+
+        match self {
+            Star => Some('*'),
+            Hash => Some('#'),
+            A => Some('A'),
+            B => Some('B'),
+            C => Some('C'),
+            D => Some('D'),
+            Plus => Some('+'),
+            Minus => Some('-'),
+            Multiply => Some('×'),
+            Divide => Some('÷'),
+            Equals => Some('='),
+            Point => Some('.'),
+            _ => match self.digit() {
+                Some(digit) => char::from_digit(digit as u32, 10),
+                None => None,
+            },
+        }
+    }
+
+    /// The key labelled with the given character. This is the reverse of
+    /// [Key::to_char].
+    pub fn from_char(ch: char) -> Option<Key> {
+        Self::ALL.into_iter().find(|key| key.to_char() == Some(ch))
+    }
+
+    /// The label of the key.
+    pub const fn label(self) -> &'static str {
+        use Key::*;
+
+        match self {
+            Num0 => "0",
+            Num1 => "1",
+            Num2 => "2",
+            Num3 => "3",
+            Num4 => "4",
+            Num5 => "5",
+            Num6 => "6",
+            Num7 => "7",
+            Num8 => "8",
+            Num9 => "9",
+            Star => "*",
+            Hash => "#",
+            A => "A",
+            B => "B",
+            C => "C",
+            D => "D",
+            Plus => "+",
+            Minus => "-",
+            Multiply => "×",
+            Divide => "÷",
+            Equals => "=",
+            Point => ".",
+            Enter => "Enter",
+            Clear => "Clear",
+            Up => "Up",
+            Down => "Down",
+            Left => "Left",
+            Right => "Right",
+            F1 => "F1",
+            F2 => "F2",
+            F3 => "F3",
+            F4 => "F4",
+            F5 => "F5",
+            F6 => "F6",
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// The error returned when a string is not the label of a [Key].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseKeyError;
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("not a key label")
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    /// Parse the label of a key, as given by [Key::label]. Letters are not
+    /// case sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|key| key.label().eq_ignore_ascii_case(s))
+            .ok_or(ParseKeyError)
+    }
+}
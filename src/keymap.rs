@@ -0,0 +1,44 @@
+//! Keymaps for common keypad layouts.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use embedded_keypad::keymap;
+//!
+//! let keypad = GpioKeypad::new(cols, rows).with_keymap(keymap::MEMBRANE_4X4);
+//! ```
+
+use crate::Key::{self, *};
+
+/// The keymap used by [GpioKeypad::new](crate::GpioKeypad::new) for a 4x4
+/// keypad, where the keys are labelled with hexadecimal values.
+pub const HEX_4X4: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xF],
+    [0x4, 0x5, 0x6, 0xE],
+    [0x7, 0x8, 0x9, 0xD],
+    [0xA, 0x0, 0xB, 0xC],
+];
+
+/// A 3x4 telephone keypad.
+pub const PHONE_3X4: [[Key; 3]; 4] = [
+    [Num1, Num2, Num3],
+    [Num4, Num5, Num6],
+    [Num7, Num8, Num9],
+    [Star, Num0, Hash],
+];
+
+/// A 4x4 membrane keypad, with letters in the right hand column.
+pub const MEMBRANE_4X4: [[Key; 4]; 4] = [
+    [Num1, Num2, Num3, A],
+    [Num4, Num5, Num6, B],
+    [Num7, Num8, Num9, C],
+    [Star, Num0, Hash, D],
+];
+
+/// A 4x4 calculator keypad, with operators in the right hand column.
+pub const CALCULATOR_4X4: [[Key; 4]; 4] = [
+    [Num7, Num8, Num9, Divide],
+    [Num4, Num5, Num6, Multiply],
+    [Num1, Num2, Num3, Minus],
+    [Clear, Num0, Equals, Plus],
+];
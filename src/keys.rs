@@ -0,0 +1,294 @@
+/// One or more keys pressed simultaneously.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keys<K = u8> {
+    One(K),
+    Two(K, K),
+    Three(K, K, K),
+    Four(K, K, K, K),
+}
+
+impl<K: Copy + PartialEq> Keys<K> {
+    /// Convert the keys to an array of `Option<K>`.
+    pub fn as_array(&self) -> [Option<K>; 4] {
+        use Keys::*;
+
+        match *self {
+            One(k0) => [Some(k0), None, None, None],
+            Two(k0, k1) => [Some(k0), Some(k1), None, None],
+            Three(k0, k1, k2) => [Some(k0), Some(k1), Some(k2), None],
+            Four(k0, k1, k2, k3) => [Some(k0), Some(k1), Some(k2), Some(k3)],
+        }
+    }
+
+    /// Determines whether a given key is among those pressed.
+    pub fn includes(&self, key: K) -> bool {
+        use Keys::*;
+
+        match *self {
+            One(k0) => k0 == key,
+            Two(k0, k1) => k0 == key || k1 == key,
+            Three(k0, k1, k2) => k0 == key || k1 == key || k2 == key,
+            Four(k0, k1, k2, k3) => k0 == key || k1 == key || k2 == key || k3 == key,
+        }
+    }
+
+    /// The number of keys pressed.
+    pub fn count(&self) -> usize {
+        use Keys::*;
+
+        match self {
+            One(..) => 1,
+            Two(..) => 2,
+            Three(..) => 3,
+            Four(..) => 4,
+        }
+    }
+
+    /// Determines whether both contain the same keys, in any order.
+    pub fn matches(&self, other: &Keys<K>) -> bool {
+        self.count() == other.count()
+            && self
+                .as_array()
+                .into_iter()
+                .flatten()
+                .all(|key| other.includes(key))
+    }
+
+    /// Create [Keys] from up to four keys. Returns [None] if there are no keys
+    /// or more than four.
+    pub(crate) fn collect<I: IntoIterator<Item = K>>(keys: I) -> Option<Self> {
+        use Keys::*;
+
+        let mut keys = keys.into_iter();
+
+        match [
+            keys.next(),
+            keys.next(),
+            keys.next(),
+            keys.next(),
+            keys.next(),
+        ] {
+            [Some(k0), None, ..] => Some(One(k0)),
+            [Some(k0), Some(k1), None, ..] => Some(Two(k0, k1)),
+            [Some(k0), Some(k1), Some(k2), None, ..] => Some(Three(k0, k1, k2)),
+            [Some(k0), Some(k1), Some(k2), Some(k3), None] => Some(Four(k0, k1, k2, k3)),
+            _ => None,
+        }
+    }
+}
+
+/// The set of keys pressed on a matrix keypad, stored as a bitmap of key
+/// positions. A [KeySet] can describe any combination of keys on a keypad of
+/// up to eight rows by eight columns.
+///
+/// Positions are given as `(row, col)`, counting from zero. To work with key
+/// values, convert to and from [Keys] using the keypad's keymap.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_keypad::KeySet;
+///
+/// let mut held = KeySet::new();
+/// held.insert(0, 1);
+/// held.insert(2, 1);
+///
+/// assert!(held.contains(2, 1));
+/// assert_eq!(held.len(), 2);
+/// assert_eq!(held.iter().collect::<Vec<_>>(), [(0, 1), (2, 1)]);
+///
+/// // Three corners of a rectangle make the fourth appear pressed.
+/// held.insert(0, 3);
+/// held.insert(2, 3);
+///
+/// assert_eq!(held.ghosts().len(), 4);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySet(u64);
+
+impl KeySet {
+    /// The maximum number of rows which can be represented.
+    pub const MAX_ROWS: usize = 8;
+
+    /// The maximum number of columns which can be represented.
+    pub const MAX_COLS: usize = 8;
+
+    /// Create an empty set.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    fn bit(row: usize, col: usize) -> u64 {
+        assert!(
+            row < Self::MAX_ROWS && col < Self::MAX_COLS,
+            "key position out of range"
+        );
+
+        1 << (row * Self::MAX_COLS + col)
+    }
+
+    /// Add the key at the given position to the set.
+    ///
+    /// # Panics
+    ///
+    /// If `row` or `col` is 8 or more.
+    pub fn insert(&mut self, row: usize, col: usize) {
+        self.0 |= Self::bit(row, col);
+    }
+
+    /// Remove the key at the given position from the set.
+    ///
+    /// # Panics
+    ///
+    /// If `row` or `col` is 8 or more.
+    pub fn remove(&mut self, row: usize, col: usize) {
+        self.0 &= !Self::bit(row, col);
+    }
+
+    /// Determines whether the key at the given position is in the set.
+    ///
+    /// # Panics
+    ///
+    /// If `row` or `col` is 8 or more.
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.0 & Self::bit(row, col) != 0
+    }
+
+    /// The number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns true if the set contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The keys which are in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// The keys which are in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The keys which are in this set but not the other.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// The keys which form the corners of a rectangle in which every corner is
+    /// pressed. On a keypad without diodes, pressing any three corners of such
+    /// a rectangle makes the fourth appear pressed, so it is not possible to
+    /// tell which of these keys are really pressed.
+    pub fn ghosts(&self) -> Self {
+        let mut ghosts = 0;
+
+        for row in 0..Self::MAX_ROWS {
+            let keys = self.row_mask(row);
+
+            for other in row + 1..Self::MAX_ROWS {
+                let shared = keys & self.row_mask(other);
+
+                if shared.count_ones() > 1 {
+                    ghosts |= (shared as u64) << (row * Self::MAX_COLS);
+                    ghosts |= (shared as u64) << (other * Self::MAX_COLS);
+                }
+            }
+        }
+
+        Self(ghosts)
+    }
+
+    fn row_mask(&self, row: usize) -> u8 {
+        (self.0 >> (row * Self::MAX_COLS)) as u8
+    }
+
+    /// Iterate over the positions in the set as `(row, col)`, ordered by row
+    /// and then by column.
+    pub fn iter(&self) -> KeySetIter {
+        KeySetIter(self.0)
+    }
+
+    /// Create a set from [Keys], using the keymap to find the position of each
+    /// key. Keys which do not appear in the keymap are ignored. If a key
+    /// appears in the keymap more than once, the first position is used.
+    pub fn from_keys<K: Copy + PartialEq, const COLS: usize, const ROWS: usize>(
+        keys: &Keys<K>,
+        keymap: &[[K; COLS]; ROWS],
+    ) -> Self {
+        let mut set = Self::new();
+
+        for key in keys.as_array().into_iter().flatten() {
+            let pos = keymap
+                .iter()
+                .enumerate()
+                .find_map(|(row, keys)| keys.iter().position(|&k| k == key).map(|col| (row, col)));
+
+            if let Some((row, col)) = pos {
+                set.insert(row, col);
+            }
+        }
+
+        set
+    }
+
+    /// Convert the set to [Keys], using the keymap to find the value of each
+    /// key. Returns [None] if the set is empty or contains more than four
+    /// keys.
+    pub fn to_keys<K: Copy + PartialEq, const COLS: usize, const ROWS: usize>(
+        &self,
+        keymap: &[[K; COLS]; ROWS],
+    ) -> Option<Keys<K>> {
+        Keys::collect(self.iter().map(|(row, col)| keymap[row][col]))
+    }
+}
+
+impl IntoIterator for KeySet {
+    type Item = (usize, usize);
+    type IntoIter = KeySetIter;
+
+    fn into_iter(self) -> KeySetIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<(usize, usize)> for KeySet {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        let mut set = Self::new();
+
+        for (row, col) in iter {
+            set.insert(row, col);
+        }
+
+        set
+    }
+}
+
+/// An iterator over the positions in a [KeySet].
+#[derive(Debug, Clone)]
+pub struct KeySetIter(u64);
+
+impl Iterator for KeySetIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+
+        Some((bit / KeySet::MAX_COLS, bit % KeySet::MAX_COLS))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for KeySetIter {}
@@ -0,0 +1,413 @@
+//! Hierarchical menus navigated with the keys of a keypad.
+
+use core::cell::RefCell;
+
+use heapless::Vec;
+
+use crate::field::{Field, FieldEvent, Format};
+use crate::{Key, KeyEvent};
+
+/// A value which can be edited from a menu. This is implemented for every
+/// [Field], so any field can be used as a menu item.
+pub trait Editor {
+    /// Update the value with an event from the keypad.
+    fn process(&mut self, event: KeyEvent<Key>) -> Option<FieldEvent<()>>;
+
+    /// The text to display while editing.
+    fn text(&self) -> &str;
+
+    /// Discard any changes made since the value was last entered.
+    fn revert(&mut self);
+}
+
+impl<F: Format, const N: usize> Editor for Field<F, N> {
+    fn process(&mut self, event: KeyEvent<Key>) -> Option<FieldEvent<()>> {
+        Some(match Field::process(self, event)? {
+            FieldEvent::Changed => FieldEvent::Changed,
+            FieldEvent::Rejected => FieldEvent::Rejected,
+            FieldEvent::Entered(_) => FieldEvent::Entered(()),
+            FieldEvent::Invalid => FieldEvent::Invalid,
+        })
+    }
+
+    fn text(&self) -> &str {
+        Field::text(self)
+    }
+
+    fn revert(&mut self) {
+        Field::revert(self)
+    }
+}
+
+/// An item in a menu, identified to the application by a value of type `A`.
+pub enum Item<'a, A> {
+    /// An item which reports its action when selected.
+    Action(&'a str, A),
+    /// An item which opens another menu when selected.
+    Submenu(&'a str, &'a [Item<'a, A>]),
+    /// An item which edits a value when selected. The value is shared with
+    /// the application through a [RefCell], so it can be read once edited.
+    Value(&'a str, A, &'a RefCell<dyn Editor + 'a>),
+}
+
+impl<'a, A> Item<'a, A> {
+    /// The label displayed for the item.
+    pub fn label(&self) -> &'a str {
+        match *self {
+            Item::Action(label, _) | Item::Submenu(label, _) | Item::Value(label, ..) => label,
+        }
+    }
+}
+
+/// The outcome of a key press in a [Menu].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent<A> {
+    /// The menu should be drawn again.
+    Changed,
+    /// An action item was selected.
+    Action(A),
+    /// A value item was edited and entered.
+    Edited(A),
+    /// A key was not accepted while editing a value.
+    Rejected,
+    /// Back was pressed in the top level menu.
+    Exit,
+}
+
+/// What to draw for the current menu, passed to the callback given to
+/// [Menu::render].
+pub struct View<'v, 'a, A> {
+    /// The label of the submenu, or [None] for the top level menu.
+    pub title: Option<&'a str>,
+    /// The items in the menu.
+    pub items: &'a [Item<'a, A>],
+    /// The index of the selected item.
+    pub selected: usize,
+    /// The text of the value being edited, if any.
+    pub editing: Option<&'v str>,
+}
+
+impl<A> View<'_, '_, A> {
+    /// The index of the first item to draw on a display with room for `rows`
+    /// items, so that the selected item is visible.
+    pub fn scroll(&self, rows: usize) -> usize {
+        (self.selected + 1).saturating_sub(rows.max(1))
+    }
+}
+
+struct Level<'a, A> {
+    title: Option<&'a str>,
+    items: &'a [Item<'a, A>],
+    selected: usize,
+}
+
+/// A menu of items, which can open submenus up to `D` levels deep.
+///
+/// By default, [Key::Up] and [Key::Down] move the selection, [Key::Enter]
+/// selects an item and [Key::Left] goes back, none of which are used by a
+/// [Field] by default. The digit keys `1` to `9` select the item with that
+/// number directly. While a value is being edited, every key except back is
+/// passed to its editor, and back discards the changes.
+///
+/// # Examples
+///
+/// ```ignore
+/// use embedded_keypad::field::{Field, Integer};
+/// use embedded_keypad::menu::{Item, Menu, MenuEvent};
+///
+/// let volume = RefCell::new(Field::<_, 4>::new(Integer::new(0, 100)).with_value(&50));
+/// let sound = [Item::Value("Volume", Setting::Volume, &volume)];
+/// let items = [
+///     Item::Submenu("Sound", &sound),
+///     Item::Action("Restart", Setting::Restart),
+/// ];
+///
+/// // On a 4x4 membrane keypad, leaving hash and star to enter and edit values.
+/// let mut menu: Menu<_> = Menu::new(&items).with_keys(Key::A, Key::B, Key::C, Key::D);
+///
+/// while let Some(event) = events.next_event() {
+///     match menu.process(event) {
+///         Some(MenuEvent::Edited(Setting::Volume)) => set_volume(volume.borrow().value()),
+///         Some(MenuEvent::Action(Setting::Restart)) => restart(),
+///         _ => {}
+///     }
+///
+///     menu.render(|view| display.draw_menu(view));
+/// }
+/// ```
+pub struct Menu<'a, A: Copy, const D: usize = 4> {
+    up: Key,
+    down: Key,
+    select: Key,
+    back: Key,
+    shortcuts: bool,
+    levels: Vec<Level<'a, A>, D>,
+    editing: bool,
+}
+
+impl<'a, A: Copy, const D: usize> Menu<'a, A, D> {
+    /// Create a menu showing the given items.
+    pub fn new(items: &'a [Item<'a, A>]) -> Self {
+        const { assert!(D > 0, "a menu must have at least one level") };
+
+        let mut levels = Vec::new();
+        let _ = levels.push(Level {
+            title: None,
+            items,
+            selected: 0,
+        });
+
+        Self {
+            up: Key::Up,
+            down: Key::Down,
+            select: Key::Enter,
+            back: Key::Left,
+            shortcuts: true,
+            levels,
+            editing: false,
+        }
+    }
+
+    /// Set the keys used to move the selection up and down, select an item,
+    /// and go back.
+    pub fn with_keys(mut self, up: Key, down: Key, select: Key, back: Key) -> Self {
+        self.up = up;
+        self.down = down;
+        self.select = select;
+        self.back = back;
+        self
+    }
+
+    /// Set whether the digit keys select items directly.
+    pub fn with_shortcuts(mut self, shortcuts: bool) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    fn level(&self) -> &Level<'a, A> {
+        self.levels
+            .last()
+            .expect("the top level menu is never removed")
+    }
+
+    fn level_mut(&mut self) -> &mut Level<'a, A> {
+        self.levels
+            .last_mut()
+            .expect("the top level menu is never removed")
+    }
+
+    /// Update the menu with an event from the keypad. Only key presses are
+    /// used.
+    pub fn process(&mut self, event: KeyEvent<Key>) -> Option<MenuEvent<A>> {
+        let KeyEvent::Pressed(key) = event else {
+            return None;
+        };
+
+        if self.editing {
+            return self.edit(key, event);
+        }
+
+        if key == self.back {
+            if self.levels.len() == 1 {
+                return Some(MenuEvent::Exit);
+            }
+
+            self.levels.pop();
+            return Some(MenuEvent::Changed);
+        }
+
+        let len = self.level().items.len();
+        let selected = self.level().selected;
+
+        if len == 0 {
+            return None;
+        }
+
+        let selected = if key == self.up {
+            (selected + len - 1) % len
+        } else if key == self.down {
+            (selected + 1) % len
+        } else if key == self.select {
+            return self.activate();
+        } else {
+            match key.digit() {
+                Some(digit @ 1..=9) if self.shortcuts && (digit as usize) <= len => {
+                    self.level_mut().selected = digit as usize - 1;
+                    return self.activate();
+                }
+                _ => return None,
+            }
+        };
+
+        self.level_mut().selected = selected;
+        Some(MenuEvent::Changed)
+    }
+
+    fn activate(&mut self) -> Option<MenuEvent<A>> {
+        let level = self.level();
+
+        match *level.items.get(level.selected)? {
+            Item::Action(_, action) => Some(MenuEvent::Action(action)),
+            Item::Submenu(title, items) => {
+                self.levels
+                    .push(Level {
+                        title: Some(title),
+                        items,
+                        selected: 0,
+                    })
+                    .ok()?;
+
+                Some(MenuEvent::Changed)
+            }
+            Item::Value(..) => {
+                self.editing = true;
+                Some(MenuEvent::Changed)
+            }
+        }
+    }
+
+    fn edit(&mut self, key: Key, event: KeyEvent<Key>) -> Option<MenuEvent<A>> {
+        let level = self.level();
+
+        let Some(&Item::Value(_, id, editor)) = level.items.get(level.selected) else {
+            self.editing = false;
+            return None;
+        };
+
+        if key == self.back {
+            editor.borrow_mut().revert();
+            self.editing = false;
+            return Some(MenuEvent::Changed);
+        }
+
+        let event = editor.borrow_mut().process(event)?;
+
+        Some(match event {
+            FieldEvent::Changed => MenuEvent::Changed,
+            FieldEvent::Entered(()) => {
+                self.editing = false;
+                MenuEvent::Edited(id)
+            }
+            FieldEvent::Rejected | FieldEvent::Invalid => MenuEvent::Rejected,
+        })
+    }
+
+    /// The selected item, if the menu is not empty.
+    pub fn selected(&self) -> Option<&'a Item<'a, A>> {
+        let level = self.level();
+        level.items.get(level.selected)
+    }
+
+    /// Returns true while the selected value is being edited.
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    /// The number of submenus open.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Close every submenu and return to the first item of the top level
+    /// menu, discarding any changes to a value being edited.
+    pub fn reset(&mut self) {
+        if self.editing {
+            if let Some(&Item::Value(_, _, editor)) = self.selected() {
+                editor.borrow_mut().revert();
+            }
+        }
+
+        self.editing = false;
+        self.levels.truncate(1);
+        self.level_mut().selected = 0;
+    }
+
+    /// Draw the menu by passing a [View] of it to the given callback, which
+    /// can draw it on any display.
+    pub fn render<R>(&self, draw: impl FnOnce(&View<'_, 'a, A>) -> R) -> R {
+        let level = self.level();
+        let editor = match self.selected() {
+            Some(Item::Value(_, _, editor)) if self.editing => Some(editor.borrow()),
+            _ => None,
+        };
+
+        draw(&View {
+            title: level.title,
+            items: level.items,
+            selected: level.selected,
+            editing: editor.as_deref().map(|editor| editor.text()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Integer;
+    use crate::testing::tap;
+    use Key::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Setting {
+        Volume,
+        Restart,
+    }
+
+    #[test]
+    fn navigation() {
+        let volume = RefCell::new(Field::<_, 4>::new(Integer::new(0, 100)).with_value(&50));
+        let sound = [Item::Value("Volume", Setting::Volume, &volume)];
+        let items = [
+            Item::Submenu("Sound", &sound),
+            Item::Action("Restart", Setting::Restart),
+        ];
+        let mut menu: Menu<_> = Menu::new(&items);
+
+        assert_eq!(tap!(menu, &[Down]), Some(MenuEvent::Changed));
+        assert_eq!(menu.selected().map(Item::label), Some("Restart"));
+        assert_eq!(tap!(menu, &[Down, Up]), Some(MenuEvent::Changed));
+        assert_eq!(menu.selected().map(Item::label), Some("Restart"));
+        assert_eq!(
+            tap!(menu, &[Enter]),
+            Some(MenuEvent::Action(Setting::Restart))
+        );
+
+        assert_eq!(tap!(menu, &[Num1]), Some(MenuEvent::Changed));
+        assert_eq!(menu.depth(), 1);
+        menu.render(|view| {
+            assert_eq!(view.title, Some("Sound"));
+            assert_eq!(view.editing, None);
+        });
+
+        assert_eq!(tap!(menu, &[Left]), Some(MenuEvent::Changed));
+        assert_eq!(tap!(menu, &[Left]), Some(MenuEvent::Exit));
+    }
+
+    #[test]
+    fn editing_a_value() {
+        let volume = RefCell::new(Field::<_, 4>::new(Integer::new(0, 100)).with_value(&50));
+        let items = [Item::Value("Volume", Setting::Volume, &volume)];
+        let mut menu: Menu<_> = Menu::new(&items);
+
+        tap!(menu, &[Enter]);
+        assert!(menu.is_editing());
+
+        // The field's own keys are passed to it.
+        assert_eq!(tap!(menu, &[Clear, Num7]), Some(MenuEvent::Changed));
+        assert_eq!(tap!(menu, &[Star, Num8]), Some(MenuEvent::Changed));
+        assert_eq!(tap!(menu, &[Num0, Num0]), Some(MenuEvent::Rejected));
+        menu.render(|view| assert_eq!(view.editing, Some("80")));
+
+        assert_eq!(
+            tap!(menu, &[Hash]),
+            Some(MenuEvent::Edited(Setting::Volume))
+        );
+        assert!(!menu.is_editing());
+        assert_eq!(volume.borrow().value(), Some(80));
+
+        // Back discards the changes.
+        tap!(menu, &[Enter, Clear, Num1, Left]);
+        assert!(!menu.is_editing());
+        assert_eq!(volume.borrow().value(), Some(80));
+    }
+}
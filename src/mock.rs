@@ -0,0 +1,758 @@
+//! Mock pins and a simulated key matrix, for testing keypad code on a host
+//! without any hardware. Requires the `mock` feature.
+//!
+//! A [Matrix] models the wiring of a keypad. Its [Matrix::columns] and
+//! [Matrix::rows] are embedded-hal pins which can be given to a
+//! [GpioKeypad](crate::GpioKeypad), and keys are pressed and released on the
+//! matrix by position.
+//!
+//! To test code which uses any [Keypad], rather than the pins of a keypad, a
+//! [MockKeypad] replays a script of [Step]s instead.
+//!
+//! Any pins, including those of a [Matrix], can be wrapped by a [Trace] to
+//! record each column written and row read, so the scan sequence can be
+//! checked.
+//!
+//! With the `eh1` feature, the pins of a [Matrix] also implement the
+//! embedded-hal 1.0 traits, so they can be wrapped in
+//! [eh1::Pin](crate::pin::eh1::Pin). With the `async` feature, the rows also
+//! implement `Wait`, treating an edge as the row reaching the new level, so
+//! a key which is already pressed is detected straight away.
+//!
+//! # Examples
+//!
+//! ```
+//! use embedded_keypad::mock::Matrix;
+//! use embedded_keypad::{GpioKeypad, Keypad, Keys};
+//!
+//! let matrix = Matrix::<3, 4>::new();
+//! let mut keypad = GpioKeypad::new(matrix.columns(), matrix.rows());
+//!
+//! matrix.press(1, 2);
+//! matrix.press(3, 0);
+//! assert_eq!(keypad.read_multi(), Ok(Some(Keys::Two(9, 5))));
+//!
+//! matrix.release_all();
+//! assert_eq!(keypad.read(), Ok(None));
+//! ```
+
+use core::cell::RefCell;
+use core::fmt;
+
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use heapless::Vec;
+
+use crate::{KeySet, Keypad, Keys, Polarity};
+
+/// The error returned by a mock pin or keypad which has been made to fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinError;
+
+struct State<const COLS: usize, const ROWS: usize> {
+    keys: KeySet,
+    bounces: [[u8; COLS]; ROWS],
+    driven: [bool; COLS],
+    failing_cols: u8,
+    failing_rows: u8,
+}
+
+/// A simulated matrix of `COLS` columns and `ROWS` rows, of up to eight by
+/// eight keys.
+///
+/// By default, the matrix has a diode for each key and both columns and rows
+/// are [Polarity::ActiveHigh]. Without diodes, pressed keys connect rows and
+/// columns together, so the matrix shows ghosting just as real hardware does.
+pub struct Matrix<const COLS: usize, const ROWS: usize> {
+    state: RefCell<State<COLS, ROWS>>,
+    col_polarity: Polarity,
+    row_polarity: Polarity,
+    diodes: bool,
+}
+
+impl<const COLS: usize, const ROWS: usize> Matrix<COLS, ROWS> {
+    /// Create a matrix with no keys pressed.
+    pub fn new() -> Self {
+        const {
+            assert!(
+                COLS <= KeySet::MAX_COLS && ROWS <= KeySet::MAX_ROWS,
+                "matrices larger than 8x8 are not supported"
+            )
+        };
+
+        Self {
+            state: RefCell::new(State {
+                keys: KeySet::new(),
+                bounces: [[0; COLS]; ROWS],
+                driven: [false; COLS],
+                failing_cols: 0,
+                failing_rows: 0,
+            }),
+            col_polarity: Polarity::ActiveHigh,
+            row_polarity: Polarity::ActiveHigh,
+            diodes: true,
+        }
+    }
+
+    /// Set the polarity of the column and row pins, which should match the
+    /// keypad under test.
+    pub fn with_polarity(mut self, cols: Polarity, rows: Polarity) -> Self {
+        self.col_polarity = cols;
+        self.row_polarity = rows;
+        self
+    }
+
+    /// Remove the diodes from the matrix, so that it shows ghosting.
+    pub fn without_diodes(mut self) -> Self {
+        self.diodes = false;
+        self
+    }
+
+    /// The column pins, in order.
+    pub fn columns(&self) -> [Column<'_, COLS, ROWS>; COLS] {
+        core::array::from_fn(|index| Column {
+            matrix: self,
+            index,
+        })
+    }
+
+    /// The row pins, in order.
+    pub fn rows(&self) -> [Row<'_, COLS, ROWS>; ROWS] {
+        core::array::from_fn(|index| Row {
+            matrix: self,
+            index,
+        })
+    }
+
+    /// Press the key at the given position.
+    pub fn press(&self, row: usize, col: usize) {
+        self.set(row, col, true, 0);
+    }
+
+    /// Release the key at the given position.
+    pub fn release(&self, row: usize, col: usize) {
+        self.set(row, col, false, 0);
+    }
+
+    /// Press the key at the given position, with contact bounce. The next
+    /// `bounces` times the key is read, it alternates between released and
+    /// pressed, starting with released, before settling as pressed.
+    pub fn press_bouncing(&self, row: usize, col: usize, bounces: u8) {
+        self.set(row, col, true, bounces);
+    }
+
+    /// Release the key at the given position, with contact bounce. The next
+    /// `bounces` times the key is read, it alternates between pressed and
+    /// released, starting with pressed, before settling as released.
+    pub fn release_bouncing(&self, row: usize, col: usize, bounces: u8) {
+        self.set(row, col, false, bounces);
+    }
+
+    /// Release every key.
+    pub fn release_all(&self) {
+        let mut state = self.state.borrow_mut();
+        state.keys = KeySet::new();
+        state.bounces = [[0; COLS]; ROWS];
+    }
+
+    /// The keys which are pressed, ignoring contact bounce.
+    pub fn pressed(&self) -> KeySet {
+        self.state.borrow().keys
+    }
+
+    /// Make the column pin at `col` return [PinError] when it is set.
+    pub fn fail_column(&self, col: usize, failing: bool) {
+        Self::set_bit(&mut self.state.borrow_mut().failing_cols, col, failing);
+    }
+
+    /// Make the row pin at `row` return [PinError] when it is read.
+    pub fn fail_row(&self, row: usize, failing: bool) {
+        Self::set_bit(&mut self.state.borrow_mut().failing_rows, row, failing);
+    }
+
+    fn set_bit(bits: &mut u8, index: usize, set: bool) {
+        if set {
+            *bits |= 1 << index;
+        } else {
+            *bits &= !(1 << index);
+        }
+    }
+
+    fn set(&self, row: usize, col: usize, pressed: bool, bounces: u8) {
+        let mut state = self.state.borrow_mut();
+
+        if pressed {
+            state.keys.insert(row, col);
+        } else {
+            state.keys.remove(row, col);
+        }
+
+        state.bounces[row][col] = bounces;
+    }
+
+    /// Whether the contacts of a key are closed, taking bounce into account.
+    fn contact(state: &State<COLS, ROWS>, row: usize, col: usize) -> bool {
+        state.keys.contains(row, col) != (state.bounces[row][col] % 2 == 1)
+    }
+
+    /// Determine the level of a row, which is active if it is connected to an
+    /// active column. Each key read directly from the row uses up one bounce.
+    fn read_row(&self, row: usize) -> Result<bool, PinError> {
+        let mut state = self.state.borrow_mut();
+
+        if state.failing_rows & (1 << row) != 0 {
+            return Err(PinError);
+        }
+
+        let active: [bool; COLS] =
+            core::array::from_fn(|col| self.col_polarity.is_active(state.driven[col]));
+
+        let is_active = if self.diodes {
+            (0..COLS).any(|col| active[col] && Self::contact(&state, row, col))
+        } else {
+            self.is_connected(&state, row, &active)
+        };
+
+        for col in (0..COLS).filter(|&col| active[col]) {
+            let bounces = &mut state.bounces[row][col];
+            *bounces = bounces.saturating_sub(1);
+        }
+
+        Ok(self.row_polarity.is_active(true) == is_active)
+    }
+
+    /// Follow pressed keys from a row to every row and column it is connected
+    /// to, and determine whether any of those columns are active.
+    fn is_connected(&self, state: &State<COLS, ROWS>, row: usize, active: &[bool; COLS]) -> bool {
+        let mut rows = 1u8 << row;
+        let mut cols = 0u8;
+
+        loop {
+            let mut next_cols = cols;
+            let mut next_rows = rows;
+
+            for r in (0..ROWS).filter(|r| rows & (1 << r) != 0) {
+                for c in (0..COLS).filter(|&c| Self::contact(state, r, c)) {
+                    next_cols |= 1 << c;
+                }
+            }
+
+            for c in (0..COLS).filter(|c| next_cols & (1 << c) != 0) {
+                for r in (0..ROWS).filter(|&r| Self::contact(state, r, c)) {
+                    next_rows |= 1 << r;
+                }
+            }
+
+            if next_cols == cols && next_rows == rows {
+                return (0..COLS).any(|c| cols & (1 << c) != 0 && active[c]);
+            }
+
+            cols = next_cols;
+            rows = next_rows;
+        }
+    }
+
+    fn drive(&self, col: usize, high: bool) -> Result<(), PinError> {
+        let mut state = self.state.borrow_mut();
+
+        if state.failing_cols & (1 << col) != 0 {
+            return Err(PinError);
+        }
+
+        state.driven[col] = high;
+        Ok(())
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> Default for Matrix<COLS, ROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A column pin of a [Matrix].
+pub struct Column<'a, const COLS: usize, const ROWS: usize> {
+    matrix: &'a Matrix<COLS, ROWS>,
+    index: usize,
+}
+
+impl<const COLS: usize, const ROWS: usize> OutputPin for Column<'_, COLS, ROWS> {
+    type Error = PinError;
+
+    fn set_high(&mut self) -> Result<(), PinError> {
+        self.matrix.drive(self.index, true)
+    }
+
+    fn set_low(&mut self) -> Result<(), PinError> {
+        self.matrix.drive(self.index, false)
+    }
+}
+
+/// A row pin of a [Matrix].
+pub struct Row<'a, const COLS: usize, const ROWS: usize> {
+    matrix: &'a Matrix<COLS, ROWS>,
+    index: usize,
+}
+
+impl<const COLS: usize, const ROWS: usize> InputPin for Row<'_, COLS, ROWS> {
+    type Error = PinError;
+
+    fn is_high(&self) -> Result<bool, PinError> {
+        self.matrix.read_row(self.index)
+    }
+
+    fn is_low(&self) -> Result<bool, PinError> {
+        self.matrix.read_row(self.index).map(|high| !high)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl embedded_hal_1::digital::Error for PinError {
+    fn kind(&self) -> embedded_hal_1::digital::ErrorKind {
+        embedded_hal_1::digital::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<const COLS: usize, const ROWS: usize> embedded_hal_1::digital::ErrorType
+    for Column<'_, COLS, ROWS>
+{
+    type Error = PinError;
+}
+
+#[cfg(feature = "eh1")]
+impl<const COLS: usize, const ROWS: usize> embedded_hal_1::digital::OutputPin
+    for Column<'_, COLS, ROWS>
+{
+    fn set_high(&mut self) -> Result<(), PinError> {
+        self.matrix.drive(self.index, true)
+    }
+
+    fn set_low(&mut self) -> Result<(), PinError> {
+        self.matrix.drive(self.index, false)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<const COLS: usize, const ROWS: usize> embedded_hal_1::digital::ErrorType
+    for Row<'_, COLS, ROWS>
+{
+    type Error = PinError;
+}
+
+#[cfg(feature = "eh1")]
+impl<const COLS: usize, const ROWS: usize> embedded_hal_1::digital::InputPin
+    for Row<'_, COLS, ROWS>
+{
+    fn is_high(&mut self) -> Result<bool, PinError> {
+        self.matrix.read_row(self.index)
+    }
+
+    fn is_low(&mut self) -> Result<bool, PinError> {
+        self.matrix.read_row(self.index).map(|high| !high)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<const COLS: usize, const ROWS: usize> Row<'_, COLS, ROWS> {
+    /// Wait until the row is at the given level. The matrix cannot wake the
+    /// task when a key is pressed, so the task is woken to poll again
+    /// straight away.
+    async fn wait_for_level(&mut self, high: bool) -> Result<(), PinError> {
+        core::future::poll_fn(|cx| match self.matrix.read_row(self.index) {
+            Ok(level) if level != high => {
+                cx.waker().wake_by_ref();
+                core::task::Poll::Pending
+            }
+            result => core::task::Poll::Ready(result.map(|_| ())),
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<const COLS: usize, const ROWS: usize> embedded_hal_async::digital::Wait
+    for Row<'_, COLS, ROWS>
+{
+    async fn wait_for_high(&mut self) -> Result<(), PinError> {
+        self.wait_for_level(true).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), PinError> {
+        self.wait_for_level(false).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), PinError> {
+        self.wait_for_level(true).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), PinError> {
+        self.wait_for_level(false).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), PinError> {
+        let high = self.matrix.read_row(self.index)?;
+        self.wait_for_level(!high).await
+    }
+}
+
+/// A step in the script of a [MockKeypad]. Each step is taken by one read of
+/// the keypad, except for [Step::Hold], which is taken by several.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step<K = u8> {
+    /// Press a key, in addition to any keys already held.
+    Press(K),
+    /// Release a key.
+    Release(K),
+    /// Replace the keys held, or release every key with [None].
+    Set(Option<Keys<K>>),
+    /// Keep the keys held for the given number of reads.
+    Hold(u32),
+    /// Fail to read the keypad, returning [PinError].
+    Fail,
+}
+
+/// A keypad which replays a script, for testing code which uses the [Keypad]
+/// trait. Every read, including [Keypad::key_is_pressed], takes the next
+/// [Step] and reports the keys held after it. Once the script is finished,
+/// the keys last held continue to be reported.
+///
+/// Up to four keys can be held at once, and they are reported in the order in
+/// which they were pressed.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_keypad::mock::{MockKeypad, Step};
+/// use embedded_keypad::{Keypad, Keys};
+///
+/// let mut keypad = MockKeypad::new(&[
+///     Step::Press(1),
+///     Step::Hold(2),
+///     Step::Press(2),
+///     Step::Release(1),
+/// ]);
+///
+/// assert_eq!(keypad.read(), Ok(Some(1)));
+/// assert_eq!(keypad.read(), Ok(Some(1)));
+/// assert_eq!(keypad.read(), Ok(Some(1)));
+/// assert_eq!(keypad.read_multi(), Ok(Some(Keys::Two(1, 2))));
+/// assert_eq!(keypad.read(), Ok(Some(2)));
+/// keypad.assert_finished();
+/// ```
+pub struct MockKeypad<'a, K = u8> {
+    script: &'a [Step<K>],
+    next: usize,
+    held: u32,
+    keys: Vec<K, 4>,
+}
+
+impl<'a, K: Copy + PartialEq> MockKeypad<'a, K> {
+    /// Create a keypad which replays the given script, starting with no keys
+    /// held.
+    pub fn new(script: &'a [Step<K>]) -> Self {
+        Self {
+            script,
+            next: 0,
+            held: 0,
+            keys: Vec::new(),
+        }
+    }
+
+    /// The number of steps which have not yet been taken.
+    pub fn remaining(&self) -> usize {
+        self.script.len() - self.next
+    }
+
+    /// Returns true if every step has been taken.
+    pub fn is_finished(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Panic if any step has not yet been taken.
+    #[track_caller]
+    pub fn assert_finished(&self) {
+        assert!(
+            self.is_finished(),
+            "{} of {} script steps were not taken",
+            self.remaining(),
+            self.script.len()
+        );
+    }
+
+    /// Take the next step of the script.
+    ///
+    /// # Panics
+    ///
+    /// If a step presses a fifth key, or releases a key which is not held.
+    fn step(&mut self) -> Result<Option<Keys<K>>, PinError> {
+        if let Some(&step) = self.script.get(self.next) {
+            match step {
+                Step::Hold(reads) => {
+                    self.held += 1;
+
+                    if self.held < reads {
+                        return Ok(self.keys());
+                    }
+
+                    self.held = 0;
+                }
+                Step::Press(key) => {
+                    if !self.keys.contains(&key) {
+                        self.keys
+                            .push(key)
+                            .ok()
+                            .expect("at most four keys can be held");
+                    }
+                }
+                Step::Release(key) => {
+                    let index = self.keys.iter().position(|&k| k == key);
+                    self.keys
+                        .remove(index.expect("released a key which was not held"));
+                }
+                Step::Set(keys) => {
+                    self.keys.clear();
+                    self.keys
+                        .extend(keys.iter().flat_map(Keys::as_array).flatten());
+                }
+                Step::Fail => {
+                    self.next += 1;
+                    return Err(PinError);
+                }
+            }
+
+            self.next += 1;
+        }
+
+        Ok(self.keys())
+    }
+
+    fn keys(&self) -> Option<Keys<K>> {
+        Keys::collect(self.keys.iter().copied())
+    }
+}
+
+impl<K: Copy + PartialEq> Keypad for MockKeypad<'_, K> {
+    type Key = K;
+    type Error = PinError;
+
+    fn key_is_pressed(&mut self) -> Result<bool, PinError> {
+        Ok(self.step()?.is_some())
+    }
+
+    fn read(&mut self) -> Result<Option<K>, PinError> {
+        Ok(self.step()?.and_then(|keys| keys.as_array()[0]))
+    }
+
+    fn read_multi(&mut self) -> Result<Option<Keys<K>>, PinError> {
+        self.step()
+    }
+}
+
+/// A pin access recorded by a [Trace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The column at the given index was set high (true) or low (false).
+    Column(usize, bool),
+    /// The row at the given index was read as high (true) or low (false).
+    Row(usize, bool),
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Access::Column(col, high) => write!(f, "C{}={}", col, high as u8),
+            Access::Row(row, high) => write!(f, "R{}={}", row, high as u8),
+        }
+    }
+}
+
+/// A record of up to `N` pin accesses, made through pins wrapped with
+/// [Trace::columns] and [Trace::rows]. Only accesses which succeed are
+/// recorded.
+///
+/// A trace is displayed as its accesses separated by spaces, such as
+/// `C0=1 C1=0 R0=1`, which is convenient for comparing against a snapshot.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_keypad::mock::{Matrix, Trace};
+/// use embedded_keypad::{GpioKeypad, Keypad};
+///
+/// let matrix = Matrix::<2, 2>::new();
+/// let trace = Trace::<32>::new();
+/// let mut keypad = GpioKeypad::new(trace.columns(matrix.columns()), trace.rows(matrix.rows()));
+///
+/// assert_eq!(keypad.read(), Ok(None));
+/// assert_eq!(trace.to_string(), "C0=1 C1=1 R0=0 R1=0");
+/// ```
+///
+/// # Panics
+///
+/// When an access is made and the trace is full.
+pub struct Trace<const N: usize> {
+    accesses: RefCell<Vec<Access, N>>,
+}
+
+impl<const N: usize> Trace<N> {
+    /// Create an empty trace.
+    pub fn new() -> Self {
+        Self {
+            accesses: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Wrap column pins so that writes to them are recorded.
+    pub fn columns<P: OutputPin, const COLS: usize>(
+        &self,
+        pins: [P; COLS],
+    ) -> [Traced<'_, P, N>; COLS] {
+        self.wrap(pins)
+    }
+
+    /// Wrap row pins so that reads from them are recorded.
+    pub fn rows<P: InputPin, const ROWS: usize>(
+        &self,
+        pins: [P; ROWS],
+    ) -> [Traced<'_, P, N>; ROWS] {
+        self.wrap(pins)
+    }
+
+    fn wrap<P, const L: usize>(&self, pins: [P; L]) -> [Traced<'_, P, N>; L] {
+        let mut index = 0;
+
+        pins.map(|pin| {
+            index += 1;
+            Traced {
+                pin,
+                index: index - 1,
+                trace: self,
+            }
+        })
+    }
+
+    fn record(&self, access: Access) {
+        self.accesses
+            .borrow_mut()
+            .push(access)
+            .expect("the trace is full");
+    }
+
+    /// The accesses recorded, in order.
+    pub fn accesses(&self) -> Vec<Access, N> {
+        self.accesses.borrow().clone()
+    }
+
+    /// Remove and return the accesses recorded.
+    pub fn take(&self) -> Vec<Access, N> {
+        core::mem::take(&mut *self.accesses.borrow_mut())
+    }
+
+    /// Remove every access recorded.
+    pub fn clear(&self) {
+        self.accesses.borrow_mut().clear();
+    }
+}
+
+impl<const N: usize> Default for Trace<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Display for Trace<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, access) in self.accesses.borrow().iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            write!(f, "{}", access)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A pin wrapped by a [Trace], which records each access to the pin.
+pub struct Traced<'a, P, const N: usize> {
+    pin: P,
+    index: usize,
+    trace: &'a Trace<N>,
+}
+
+impl<P, const N: usize> Traced<'_, P, N> {
+    /// Returns the wrapped pin.
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: OutputPin, const N: usize> OutputPin for Traced<'_, P, N> {
+    type Error = P::Error;
+
+    fn set_high(&mut self) -> Result<(), P::Error> {
+        self.pin.set_high()?;
+        self.trace.record(Access::Column(self.index, true));
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), P::Error> {
+        self.pin.set_low()?;
+        self.trace.record(Access::Column(self.index, false));
+        Ok(())
+    }
+}
+
+impl<P: InputPin, const N: usize> InputPin for Traced<'_, P, N> {
+    type Error = P::Error;
+
+    fn is_high(&self) -> Result<bool, P::Error> {
+        let high = self.pin.is_high()?;
+        self.trace.record(Access::Row(self.index, high));
+        Ok(high)
+    }
+
+    fn is_low(&self) -> Result<bool, P::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_fail() {
+        let mut keypad = MockKeypad::new(&[
+            Step::Set(Some(Keys::Three('a', 'b', 'c'))),
+            Step::Fail,
+            Step::Release('b'),
+            Step::Set(None),
+        ]);
+
+        assert_eq!(keypad.read_multi(), Ok(Some(Keys::Three('a', 'b', 'c'))));
+        assert_eq!(keypad.read_multi(), Err(PinError));
+        assert_eq!(keypad.read_multi(), Ok(Some(Keys::Two('a', 'c'))));
+        assert_eq!(keypad.remaining(), 1);
+        assert_eq!(keypad.key_is_pressed(), Ok(false));
+        assert_eq!(keypad.read(), Ok(None));
+        keypad.assert_finished();
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 2 script steps were not taken")]
+    fn unfinished_script() {
+        let mut keypad = MockKeypad::new(&[Step::Press(1), Step::Release(1)]);
+
+        keypad.read().unwrap();
+        keypad.assert_finished();
+    }
+
+    #[test]
+    #[should_panic(expected = "released a key which was not held")]
+    fn release_without_press() {
+        let mut keypad = MockKeypad::new(&[Step::Release(1)]);
+        keypad.read().unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+//! The pins used to scan a [GpioKeypad](crate::GpioKeypad).
+//!
+//! Any embedded-hal 0.2 `OutputPin` can be used as a [ColumnPin], and any
+//! `InputPin` can be used as a [RowPin]. With the `eh1` feature, embedded-hal
+//! 1.0 pins can be used by wrapping them in `eh1::Pin`.
+
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+/// A pin which drives a column of the keypad.
+pub trait ColumnPin {
+    /// The error returned when the pin could not be set.
+    type Error;
+
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+}
+
+/// A pin which reads a row of the keypad.
+pub trait RowPin {
+    /// The error returned when the pin could not be read.
+    type Error;
+
+    fn is_high(&mut self) -> Result<bool, Self::Error>;
+}
+
+impl<P: OutputPin> ColumnPin for P {
+    type Error = P::Error;
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        OutputPin::set_high(self)
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        OutputPin::set_low(self)
+    }
+}
+
+impl<P: InputPin> RowPin for P {
+    type Error = P::Error;
+
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        InputPin::is_high(self)
+    }
+}
+
+/// Support for embedded-hal 1.0 pins.
+#[cfg(feature = "eh1")]
+pub mod eh1 {
+    use embedded_hal_1::digital::{InputPin, OutputPin};
+
+    use super::{ColumnPin, RowPin};
+
+    /// Wraps an embedded-hal 1.0 pin so that it can be used by a
+    /// [GpioKeypad](crate::GpioKeypad). With the `async` feature, keypads with
+    /// rows which implement `embedded_hal_async::digital::Wait` can also use
+    /// `GpioKeypad::wait_for_key`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let keypad = GpioKeypad::new(cols.map(eh1::Pin), rows.map(eh1::Pin));
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Pin<P>(pub P);
+
+    impl<P> Pin<P> {
+        /// Returns the wrapped pin.
+        pub fn into_inner(self) -> P {
+            self.0
+        }
+    }
+
+    impl<P: OutputPin> ColumnPin for Pin<P> {
+        type Error = P::Error;
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0.set_high()
+        }
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0.set_low()
+        }
+    }
+
+    impl<P: InputPin> RowPin for Pin<P> {
+        type Error = P::Error;
+
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            self.0.is_high()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Pin;
+        use crate::mock::{Matrix, PinError};
+        use crate::{Error, GpioKeypad, Keypad, Keys};
+
+        #[test]
+        fn keypad_with_eh1_pins() {
+            let matrix = Matrix::<3, 2>::new();
+            let mut keypad = GpioKeypad::new(matrix.columns().map(Pin), matrix.rows().map(Pin));
+
+            matrix.press(0, 1);
+            matrix.press(1, 2);
+            assert_eq!(keypad.read_multi(), Ok(Some(Keys::Two(1, 5))));
+
+            matrix.fail_column(0, true);
+            assert_eq!(keypad.read(), Err(Error::Column(0, PinError)));
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use core::fmt;
+
+use crate::{Clock, Key, KeyEvent};
+
+/// The outcome of a key press or timeout during [PinEntry].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinEvent {
+    /// A digit was added or removed, leaving the given number of digits.
+    Changed(usize),
+    /// The PIN was entered correctly.
+    Accepted,
+    /// The PIN was incorrect. The given number of attempts remain before
+    /// entry is locked.
+    Rejected(u8),
+    /// Enter was pressed before the minimum number of digits were entered.
+    TooShort,
+    /// Entry is locked for the given number of ticks, after too many
+    /// incorrect attempts.
+    Locked(u32),
+    /// Entry is no longer locked.
+    Unlocked,
+    /// The digits were cleared because no key was pressed before the
+    /// inactivity timeout.
+    TimedOut,
+}
+
+/// The digits entered so far, displayed as one mask character per digit.
+#[derive(Debug, Clone, Copy)]
+pub struct Masked {
+    len: usize,
+    mask: char,
+}
+
+impl fmt::Display for Masked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.len {
+            fmt::Write::write_char(f, self.mask)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare two byte slices in constant time, so that the time taken does not
+/// reveal how many bytes match.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let diff = a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b));
+    core::hint::black_box(diff) == 0
+}
+
+/// PIN entry using the digit keys of a keypad, for PINs of up to `MAX`
+/// digits. The PIN is checked by hashing the digits entered, as ASCII, with a
+/// user supplied function, and comparing the result to a stored hash in
+/// constant time.
+///
+/// By default, [Key::Hash] enters the PIN, [Key::Star] removes the last digit
+/// and [Key::Clear] removes every digit. After three incorrect attempts, entry
+/// is locked for 30,000 ticks (30 seconds with a millisecond clock), and each
+/// time it is locked again the lockout is twice as long. There is no
+/// inactivity timeout unless one is set with [PinEntry::with_timeout].
+///
+/// # Examples
+///
+/// ```ignore
+/// // Allow 4 to 8 digits, clear after 30s of inactivity, and lock for 30s
+/// // after three failed attempts.
+/// let mut pin: PinEntry<_, _, 8, 32> =
+///     PinEntry::new(|| timer.millis(), |pin| sha256(SALT, pin), STORED_HASH)
+///         .with_min_length(4)
+///         .with_timeout(30_000)
+///         .with_lockout(3, 30_000);
+///
+/// while let Some(event) = events.next_event() {
+///     match pin.process(event) {
+///         Some(PinEvent::Accepted) => unlock_door(),
+///         Some(PinEvent::Rejected(_)) => display.show("Wrong PIN"),
+///         Some(PinEvent::Locked(ticks)) => display.show_lockout(ticks),
+///         _ => display.show(pin.masked('*')),
+///     }
+/// }
+/// ```
+pub struct PinEntry<C, F, const MAX: usize, const H: usize>
+where
+    C: Clock,
+    F: Fn(&[u8]) -> [u8; H],
+{
+    clock: C,
+    hash: F,
+    stored: [u8; H],
+    min_len: usize,
+    enter: Key,
+    backspace: Key,
+    clear: Key,
+    timeout: Option<u32>,
+    attempts: u8,
+    lockout: u32,
+    digits: [u8; MAX],
+    len: usize,
+    last_press: u32,
+    failures: u8,
+    lockouts: u32,
+    locked_at: Option<u32>,
+}
+
+impl<C, F, const MAX: usize, const H: usize> PinEntry<C, F, MAX, H>
+where
+    C: Clock,
+    F: Fn(&[u8]) -> [u8; H],
+{
+    /// Create a PIN entry which accepts the PIN whose hash, as calculated by
+    /// `hash`, is `stored`.
+    pub fn new(clock: C, hash: F, stored: [u8; H]) -> Self {
+        let last_press = clock.now();
+
+        Self {
+            clock,
+            hash,
+            stored,
+            min_len: 1,
+            enter: Key::Hash,
+            backspace: Key::Star,
+            clear: Key::Clear,
+            timeout: None,
+            attempts: 3,
+            lockout: 30_000,
+            digits: [0; MAX],
+            len: 0,
+            last_press,
+            failures: 0,
+            lockouts: 0,
+            locked_at: None,
+        }
+    }
+
+    /// Set the minimum number of digits which must be entered.
+    pub fn with_min_length(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Set the keys used to enter the PIN, remove the last digit and remove
+    /// every digit.
+    pub fn with_keys(mut self, enter: Key, backspace: Key, clear: Key) -> Self {
+        self.enter = enter;
+        self.backspace = backspace;
+        self.clear = clear;
+        self
+    }
+
+    /// Clear the digits when no key is pressed for `timeout` ticks.
+    pub fn with_timeout(mut self, timeout: u32) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Lock entry for `lockout` ticks after `attempts` incorrect attempts.
+    /// Each time entry is locked again before a correct PIN is entered, the
+    /// lockout is doubled. A lockout of zero disables locking, so incorrect
+    /// attempts are not counted. Zero attempts is treated as one, so entry is
+    /// locked after the first incorrect attempt.
+    pub fn with_lockout(mut self, attempts: u8, lockout: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self.lockout = lockout;
+        self
+    }
+
+    /// Update the entry with an event from the keypad. Only key presses are
+    /// used. If [PinEntry::poll] has an event to report, such as the end of a
+    /// lockout, that event is returned and the key press is ignored.
+    pub fn process(&mut self, event: KeyEvent<Key>) -> Option<PinEvent> {
+        let KeyEvent::Pressed(key) = event else {
+            return None;
+        };
+
+        if let Some(event) = self.poll() {
+            return Some(event);
+        }
+
+        self.last_press = self.clock.now();
+
+        if key == self.enter {
+            return Some(self.verify());
+        }
+
+        if key == self.clear {
+            self.reset();
+        } else if key == self.backspace {
+            if self.len > 0 {
+                self.len -= 1;
+                self.digits[self.len] = 0;
+            }
+        } else if let Some(digit) = key.digit() {
+            if self.len == MAX {
+                return None;
+            }
+
+            self.digits[self.len] = b'0' + digit;
+            self.len += 1;
+        } else {
+            return None;
+        }
+
+        Some(PinEvent::Changed(self.len))
+    }
+
+    /// Check for the inactivity timeout, and for the end of a lockout. This
+    /// should be called regularly, even when there are no new events. While
+    /// entry is locked, [PinEvent::Locked] is returned with the time
+    /// remaining.
+    pub fn poll(&mut self) -> Option<PinEvent> {
+        if let Some(locked_at) = self.locked_at {
+            let elapsed = self.clock.since(locked_at);
+            let duration = self.lockout_duration();
+
+            if elapsed < duration {
+                return Some(PinEvent::Locked(duration - elapsed));
+            }
+
+            self.locked_at = None;
+            self.failures = 0;
+            return Some(PinEvent::Unlocked);
+        }
+
+        match self.timeout {
+            Some(timeout) if self.len > 0 && self.clock.since(self.last_press) >= timeout => {
+                self.reset();
+                Some(PinEvent::TimedOut)
+            }
+            _ => None,
+        }
+    }
+
+    /// The number of digits entered.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no digits have been entered.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The digits entered, masked for display.
+    pub fn masked(&self, mask: char) -> Masked {
+        Masked {
+            len: self.len,
+            mask,
+        }
+    }
+
+    /// Returns true while entry is locked after too many incorrect attempts.
+    pub fn is_locked(&self) -> bool {
+        self.locked_at.is_some()
+    }
+
+    /// Remove every digit entered.
+    pub fn reset(&mut self) {
+        self.digits = [0; MAX];
+        self.len = 0;
+    }
+
+    fn verify(&mut self) -> PinEvent {
+        if self.len < self.min_len {
+            return PinEvent::TooShort;
+        }
+
+        let hash = (self.hash)(&self.digits[..self.len]);
+        let accepted = constant_time_eq(&hash, &self.stored);
+        self.reset();
+
+        if accepted {
+            self.failures = 0;
+            self.lockouts = 0;
+            return PinEvent::Accepted;
+        }
+
+        if self.lockout == 0 {
+            return PinEvent::Rejected(self.attempts);
+        }
+
+        self.failures = self.failures.saturating_add(1);
+
+        if self.failures < self.attempts {
+            return PinEvent::Rejected(self.attempts - self.failures);
+        }
+
+        self.lockouts = self.lockouts.saturating_add(1);
+        self.locked_at = Some(self.clock.now());
+        PinEvent::Locked(self.lockout_duration())
+    }
+
+    /// The length of the current lockout, which doubles with each lockout up
+    /// to half the range of the clock.
+    fn lockout_duration(&self) -> u32 {
+        let doublings = self.lockouts.saturating_sub(1).min(31);
+
+        self.lockout
+            .checked_shl(doublings)
+            .filter(|duration| duration >> doublings == self.lockout)
+            .unwrap_or(u32::MAX)
+            .min(u32::MAX / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::testing::tap;
+    use Key::*;
+
+    /// A stand in for a real hash, which is enough to tell PINs apart.
+    fn hash(pin: &[u8]) -> [u8; 4] {
+        let mut hash = [0; 4];
+        hash[..pin.len().min(4)].copy_from_slice(&pin[..pin.len().min(4)]);
+        hash
+    }
+
+    #[test]
+    fn accept_and_reject() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> =
+            PinEntry::new(|| now.get(), hash, *b"1234").with_min_length(4);
+
+        assert_eq!(tap!(pin, &[Num1, Num2, Num3]), Some(PinEvent::Changed(3)));
+        assert_eq!(
+            pin.process(KeyEvent::Pressed(Hash)),
+            Some(PinEvent::TooShort)
+        );
+        assert_eq!(std::format!("{}", pin.masked('*')), "***");
+
+        assert_eq!(tap!(pin, &[Num5, Hash]), Some(PinEvent::Rejected(2)));
+        assert!(pin.is_empty());
+
+        assert_eq!(
+            tap!(pin, &[Num1, Num2, Num3, Num5, Star, Num4, Hash]),
+            Some(PinEvent::Accepted)
+        );
+
+        // A correct PIN resets the attempts remaining.
+        assert_eq!(
+            tap!(pin, &[Num4, Num3, Num2, Num1, Hash]),
+            Some(PinEvent::Rejected(2))
+        );
+    }
+
+    #[test]
+    fn lockout_doubles() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> =
+            PinEntry::new(|| now.get(), hash, *b"1234").with_lockout(2, 100);
+        let wrong = [Num9, Hash];
+
+        assert_eq!(tap!(pin, &wrong), Some(PinEvent::Rejected(1)));
+        assert_eq!(tap!(pin, &wrong), Some(PinEvent::Locked(100)));
+        assert!(pin.is_locked());
+
+        now.set(40);
+        assert_eq!(tap!(pin, &[Num1]), Some(PinEvent::Locked(60)));
+        assert_eq!(pin.len(), 0);
+
+        now.set(100);
+        assert_eq!(pin.poll(), Some(PinEvent::Unlocked));
+        assert!(!pin.is_locked());
+
+        assert_eq!(tap!(pin, &wrong), Some(PinEvent::Rejected(1)));
+        assert_eq!(tap!(pin, &wrong), Some(PinEvent::Locked(200)));
+
+        now.set(300);
+        assert_eq!(pin.poll(), Some(PinEvent::Unlocked));
+        assert_eq!(
+            tap!(pin, &[Num1, Num2, Num3, Num4, Hash]),
+            Some(PinEvent::Accepted)
+        );
+
+        // A correct PIN resets the lockout.
+        assert_eq!(tap!(pin, &wrong), Some(PinEvent::Rejected(1)));
+        assert_eq!(tap!(pin, &wrong), Some(PinEvent::Locked(100)));
+    }
+
+    #[test]
+    fn default_lockout() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> = PinEntry::new(|| now.get(), hash, *b"1234");
+
+        tap!(pin, &[Num9, Hash, Num9, Hash]);
+        assert_eq!(tap!(pin, &[Num9, Hash]), Some(PinEvent::Locked(30_000)));
+    }
+
+    #[test]
+    fn zero_lockout_never_locks() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> =
+            PinEntry::new(|| now.get(), hash, *b"1234").with_lockout(1, 0);
+
+        for _ in 0..4 {
+            assert_eq!(tap!(pin, &[Num9, Hash]), Some(PinEvent::Rejected(1)));
+        }
+
+        assert!(!pin.is_locked());
+        assert_eq!(pin.poll(), None);
+    }
+
+    #[test]
+    fn zero_attempts_locks_after_one() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> =
+            PinEntry::new(|| now.get(), hash, *b"1234").with_lockout(0, 100);
+
+        assert_eq!(tap!(pin, &[Num9, Hash]), Some(PinEvent::Locked(100)));
+    }
+
+    #[test]
+    fn inactivity_timeout() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> =
+            PinEntry::new(|| now.get(), hash, *b"1234").with_timeout(1000);
+
+        assert_eq!(pin.poll(), None);
+
+        tap!(pin, &[Num1, Num2]);
+        now.set(999);
+        assert_eq!(pin.poll(), None);
+        assert_eq!(tap!(pin, &[Num3]), Some(PinEvent::Changed(3)));
+
+        now.set(1998);
+        assert_eq!(pin.poll(), None);
+
+        now.set(1999);
+        assert_eq!(pin.poll(), Some(PinEvent::TimedOut));
+        assert!(pin.is_empty());
+        assert_eq!(pin.poll(), None);
+    }
+
+    #[test]
+    fn process_reports_events_from_poll() {
+        let now = Cell::new(0);
+        let mut pin: PinEntry<_, _, 4, 4> = PinEntry::new(|| now.get(), hash, *b"1234")
+            .with_timeout(1000)
+            .with_lockout(1, 100);
+
+        assert_eq!(tap!(pin, &[Num9, Hash]), Some(PinEvent::Locked(100)));
+
+        now.set(100);
+        assert_eq!(tap!(pin, &[Num1]), Some(PinEvent::Unlocked));
+        assert_eq!(tap!(pin, &[Num1]), Some(PinEvent::Changed(1)));
+
+        now.set(1100);
+        assert_eq!(tap!(pin, &[Num2]), Some(PinEvent::TimedOut));
+        assert!(pin.is_empty());
+    }
+}
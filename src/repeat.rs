@@ -0,0 +1,184 @@
+use crate::{Clock, EventQueue, KeyEvent, Overflow};
+
+#[derive(Debug, Clone, Copy)]
+struct Held<K> {
+    key: K,
+    since: u32,
+    interval: u32,
+}
+
+/// Adds typematic repeat to the [KeyEvent]s of a keypad. Once a key has been
+/// held for the initial delay, a [KeyEvent::Repeated] event is generated for
+/// it at the repeat rate until it is released. Up to `N` held keys are
+/// repeated at once, and up to `N` events are queued.
+///
+/// Every event processed is passed through, in order, with the repeated
+/// events added between them.
+///
+/// # Examples
+///
+/// ```ignore
+/// // Repeat after 500ms, then every 100ms, except for the enter key.
+/// let mut repeat: AutoRepeat<_, 4> =
+///     AutoRepeat::new(|| timer.millis(), 500, 100).with_filter(|key| key != ENTER);
+///
+/// loop {
+///     events.poll()?;
+///
+///     while let Some(event) = events.next_event() {
+///         repeat.process(event);
+///     }
+///
+///     repeat.poll();
+///
+///     while let Some(event) = repeat.next_event() {
+///         match event {
+///             KeyEvent::Pressed(key) | KeyEvent::Repeated(key) => scroll(key),
+///             _ => (),
+///         }
+///     }
+/// }
+/// ```
+pub struct AutoRepeat<C: Clock, const N: usize, K: Copy = u8> {
+    clock: C,
+    delay: u32,
+    rate: u32,
+    filter: fn(K) -> bool,
+    held: [Option<Held<K>>; N],
+    queue: EventQueue<KeyEvent<K>, N>,
+}
+
+impl<C: Clock, const N: usize, K: Copy + PartialEq> AutoRepeat<C, N, K> {
+    /// Repeat keys once they have been held for `delay` ticks, and then every
+    /// `rate` ticks.
+    pub fn new(clock: C, delay: u32, rate: u32) -> Self {
+        Self {
+            clock,
+            delay,
+            rate,
+            filter: |_| true,
+            held: [None; N],
+            queue: EventQueue::new(Overflow::DropOldest),
+        }
+    }
+
+    /// Only repeat the keys for which `filter` returns true. By default, every
+    /// key is repeated.
+    pub fn with_filter(mut self, filter: fn(K) -> bool) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Pass an event from the keypad through, and start or stop repeating the
+    /// key it refers to.
+    pub fn process(&mut self, event: KeyEvent<K>) {
+        let now = self.clock.now();
+        let key = event.key();
+        let slot = self
+            .held
+            .iter()
+            .position(|held| held.is_some_and(|held| held.key == key));
+
+        match event {
+            KeyEvent::Pressed(key) if (self.filter)(key) => {
+                let slot = slot.or_else(|| self.held.iter().position(Option::is_none));
+
+                if let Some(slot) = slot {
+                    self.held[slot] = Some(Held {
+                        key,
+                        since: now,
+                        interval: self.delay,
+                    });
+                }
+            }
+            KeyEvent::Released(_) => {
+                if let Some(slot) = slot {
+                    self.held[slot] = None;
+                }
+            }
+            _ => (),
+        }
+
+        self.queue.push(event);
+    }
+
+    /// Generate repeated events for keys which have been held long enough.
+    /// This should be called regularly, even when there are no new events.
+    pub fn poll(&mut self) {
+        let now = self.clock.now();
+
+        for held in self.held.iter_mut().flatten() {
+            if now.wrapping_sub(held.since) >= held.interval {
+                self.queue.push(KeyEvent::Repeated(held.key));
+                held.since = now;
+                held.interval = self.rate;
+            }
+        }
+    }
+
+    /// Remove the oldest event from the queue.
+    pub fn next_event(&mut self) -> Option<KeyEvent<K>> {
+        self.queue.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::testing::drain;
+
+    #[test]
+    fn delay_and_rate() {
+        let now = Cell::new(0);
+        let mut repeat: AutoRepeat<_, 8> = AutoRepeat::new(|| now.get(), 500, 100);
+
+        repeat.process(KeyEvent::Pressed(1));
+
+        for time in [499, 500, 599, 600, 700] {
+            now.set(time);
+            repeat.poll();
+        }
+
+        repeat.process(KeyEvent::Released(1));
+        now.set(1000);
+        repeat.poll();
+
+        assert_eq!(
+            drain(|| repeat.next_event()),
+            [
+                Some(KeyEvent::Pressed(1)),
+                Some(KeyEvent::Repeated(1)),
+                Some(KeyEvent::Repeated(1)),
+                Some(KeyEvent::Repeated(1)),
+                Some(KeyEvent::Released(1)),
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn filter() {
+        let now = Cell::new(0);
+        let mut repeat: AutoRepeat<_, 8> =
+            AutoRepeat::new(|| now.get(), 500, 100).with_filter(|key| key != 2);
+
+        repeat.process(KeyEvent::Pressed(1));
+        repeat.process(KeyEvent::Pressed(2));
+        now.set(500);
+        repeat.poll();
+
+        assert_eq!(
+            drain(|| repeat.next_event()),
+            [
+                Some(KeyEvent::Pressed(1)),
+                Some(KeyEvent::Pressed(2)),
+                Some(KeyEvent::Repeated(1)),
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,518 @@
+//! A keypad simulated in a terminal, so that keypad applications can be
+//! developed on a host before hardware is available. Requires the `sim`
+//! feature.
+//!
+//! A [Simulator] implements [Keypad] using keys typed in the terminal, and
+//! draws the keypad showing which keys are held. Terminal keys are bound to
+//! keypad positions with [Bindings].
+//!
+//! Most terminals only report when a key is typed, not when it is released,
+//! so by default a typed key is held for a short time and then released. In
+//! toggle mode, switched with Tab, each typed key is held until it is typed
+//! again, so that several keys can be held at once. Terminals which report
+//! releases are detected and used automatically. Esc or Ctrl-C quits.
+
+use core::fmt;
+use std::io::{self, Stdout, Write};
+use std::string::{String, ToString};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent as TermKeyEvent, KeyEventKind, KeyModifiers,
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, execute, queue, terminal};
+
+use crate::{KeySet, Keypad, Keys};
+
+/// An error which occurred while reading a [Simulator].
+#[derive(Debug)]
+pub enum Error {
+    /// The terminal could not be used.
+    Io(io::Error),
+    /// The user asked to quit.
+    Quit,
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "terminal error: {}", err),
+            Error::Quit => f.write_str("quit"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// An error in the configuration given to [Bindings::parse].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A binding on the given line is not a single character.
+    InvalidKey(usize, String),
+    /// The configuration has more than eight rows or columns.
+    TooLarge,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::InvalidKey(line, key) => {
+                write!(f, "line {}: `{}` is not a single character", line, key)
+            }
+            ConfigError::TooLarge => f.write_str("keypads larger than 8x8 are not supported"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The terminal keys bound to each position of a [Simulator]. Letters are
+/// bound without regard to case.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    keys: Vec<(char, usize, usize)>,
+}
+
+impl Bindings {
+    /// Create bindings with no keys bound.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind a terminal key to the given position, replacing any existing
+    /// binding of the key.
+    pub fn bind(mut self, key: char, row: usize, col: usize) -> Self {
+        let key = key.to_ascii_lowercase();
+
+        self.keys.retain(|&(k, ..)| k != key);
+        self.keys.push((key, row, col));
+        self
+    }
+
+    /// Parse bindings laid out as a grid. Each line is a row of the keypad,
+    /// and each character on it, separated by spaces, is bound to the next
+    /// column. A `.` leaves the position unbound. Blank lines and lines
+    /// starting with `#` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_keypad::sim::Bindings;
+    ///
+    /// // Use the left of a PC keyboard as a 3x4 keypad.
+    /// let bindings = Bindings::parse("
+    ///     1 2 3
+    ///     q w e
+    ///     a s d
+    ///     z x c
+    /// ");
+    ///
+    /// assert_eq!(bindings.unwrap().position('W'), Some((1, 1)));
+    /// ```
+    pub fn parse(config: &str) -> Result<Self, ConfigError> {
+        let mut bindings = Self::new();
+        let lines = config
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        for (row, (number, line)) in lines.enumerate() {
+            for (col, key) in line.split_whitespace().enumerate() {
+                if row >= KeySet::MAX_ROWS || col >= KeySet::MAX_COLS {
+                    return Err(ConfigError::TooLarge);
+                }
+
+                let mut chars = key.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some('.'), None) => {}
+                    (Some(key), None) => bindings = bindings.bind(key, row, col),
+                    _ => return Err(ConfigError::InvalidKey(number, key.to_string())),
+                }
+            }
+        }
+
+        Ok(bindings)
+    }
+
+    /// The position the terminal key is bound to.
+    pub fn position(&self, key: char) -> Option<(usize, usize)> {
+        let key = key.to_ascii_lowercase();
+
+        self.keys
+            .iter()
+            .find(|&&(k, ..)| k == key)
+            .map(|&(_, row, col)| (row, col))
+    }
+
+    /// The terminal key bound to the given position.
+    pub fn key(&self, row: usize, col: usize) -> Option<char> {
+        self.keys
+            .iter()
+            .find(|&&(_, r, c)| (r, c) == (row, col))
+            .map(|&(key, ..)| key)
+    }
+}
+
+/// A keypad simulated in the terminal, which reports keys from the keymap
+/// while their terminal keys are held. The terminal is restored when the
+/// simulator is dropped.
+///
+/// By default, each key whose label is a single character is bound to that
+/// character.
+///
+/// # Examples
+///
+/// ```ignore
+/// use embedded_keypad::{keymap, sim::Simulator};
+///
+/// // The same application code can use a GpioKeypad on the device.
+/// let mut keypad = Simulator::new(keymap::PHONE_3X4)?;
+///
+/// loop {
+///     if let Some(key) = keypad.read()? {
+///         keypad.set_display(&format!("Got key: {}.", key))?;
+///     }
+/// }
+/// ```
+pub struct Simulator<K, const COLS: usize, const ROWS: usize> {
+    keymap: [[K; COLS]; ROWS],
+    labels: [[String; COLS]; ROWS],
+    bindings: Bindings,
+    hold: Duration,
+    held: KeySet,
+    taps: [[Option<Instant>; COLS]; ROWS],
+    deferred: Option<TermKeyEvent>,
+    toggle: bool,
+    releases: bool,
+    display: String,
+    out: Stdout,
+}
+
+impl<K, const COLS: usize, const ROWS: usize> Simulator<K, COLS, ROWS>
+where
+    K: Copy + PartialEq + fmt::Display,
+{
+    /// Take over the terminal and draw a keypad with the given keymap.
+    pub fn new(keymap: [[K; COLS]; ROWS]) -> io::Result<Self> {
+        const {
+            assert!(
+                COLS <= KeySet::MAX_COLS && ROWS <= KeySet::MAX_ROWS,
+                "keypads larger than 8x8 are not supported"
+            )
+        };
+
+        let labels = keymap.map(|keys| keys.map(|key| key.to_string()));
+        let mut bindings = Bindings::new();
+
+        for (row, labels) in labels.iter().enumerate() {
+            for (col, label) in labels.iter().enumerate() {
+                let mut chars = label.chars();
+
+                if let (Some(key), None) = (chars.next(), chars.next()) {
+                    bindings = bindings.bind(key, row, col);
+                }
+            }
+        }
+
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        let releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+
+        if releases {
+            execute!(
+                out,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+        }
+
+        let mut simulator = Self {
+            keymap,
+            labels,
+            bindings,
+            hold: Duration::from_millis(150),
+            held: KeySet::new(),
+            taps: [[None; COLS]; ROWS],
+            deferred: None,
+            toggle: false,
+            releases,
+            display: String::new(),
+            out,
+        };
+
+        simulator.draw()?;
+        Ok(simulator)
+    }
+
+    /// Replace the terminal keys bound to each position.
+    pub fn with_bindings(mut self, bindings: Bindings) -> io::Result<Self> {
+        self.bindings = bindings;
+        self.draw()?;
+        Ok(self)
+    }
+
+    /// Set how long a typed key is held for, when the terminal does not report
+    /// releases.
+    pub fn with_hold(mut self, hold: Duration) -> Self {
+        self.hold = hold;
+        self
+    }
+
+    /// Show text below the keypad, in place of the application's display.
+    pub fn set_display(&mut self, text: &str) -> io::Result<()> {
+        if self.display != text {
+            self.display = text.to_string();
+            self.draw()?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle the keys typed since the last update, and release any typed
+    /// keys which have been held long enough.
+    fn update(&mut self) -> Result<KeySet, Error> {
+        let held = self.held;
+        let mut redraw = false;
+
+        // Handle one keystroke per update, so every keystroke is seen by at
+        // least one read.
+        let mut typed = match self.deferred.take() {
+            Some(key) => {
+                redraw |= self.key(key)?;
+                true
+            }
+            None => false,
+        };
+
+        while self.deferred.is_none() && event::poll(Duration::ZERO)? {
+            match event::read()? {
+                Event::Key(key) if typed => self.deferred = Some(key),
+                Event::Key(key) => {
+                    redraw |= self.key(key)?;
+                    typed = true;
+                }
+                Event::Resize(..) => redraw = true,
+                _ => {}
+            }
+        }
+
+        let now = Instant::now();
+
+        for (row, taps) in self.taps.iter_mut().enumerate() {
+            for (col, tap) in taps.iter_mut().enumerate() {
+                if tap.is_some_and(|released_at| released_at <= now) {
+                    *tap = None;
+                    self.held.remove(row, col);
+                }
+            }
+        }
+
+        if redraw || self.held != held {
+            self.draw()?;
+        }
+
+        Ok(self.held)
+    }
+
+    /// Handle a key typed in the terminal. Returns true if the keypad should
+    /// be drawn again.
+    fn key(&mut self, key: TermKeyEvent) -> Result<bool, Error> {
+        let pressed = key.kind != KeyEventKind::Release;
+
+        match key.code {
+            KeyCode::Esc => Err(Error::Quit),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Err(Error::Quit),
+            KeyCode::Tab if key.kind == KeyEventKind::Press => {
+                self.toggle = !self.toggle;
+                self.held = KeySet::new();
+                self.taps = [[None; COLS]; ROWS];
+                Ok(true)
+            }
+            KeyCode::Char(ch) => {
+                let Some((row, col)) = self.bindings.position(ch) else {
+                    return Ok(false);
+                };
+
+                if row >= ROWS || col >= COLS {
+                    return Ok(false);
+                }
+
+                if self.toggle {
+                    if key.kind == KeyEventKind::Press {
+                        if self.held.contains(row, col) {
+                            self.held.remove(row, col);
+                        } else {
+                            self.held.insert(row, col);
+                        }
+                    }
+                } else if key.kind == KeyEventKind::Press && self.held.contains(row, col) {
+                    // Release the key for one read before pressing it again.
+                    self.held.remove(row, col);
+                    self.taps[row][col] = None;
+                    self.deferred = Some(key);
+                } else if pressed {
+                    self.held.insert(row, col);
+                    self.taps[row][col] = (!self.releases).then(|| Instant::now() + self.hold);
+                } else {
+                    self.held.remove(row, col);
+                }
+
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let width = self
+            .labels
+            .iter()
+            .flatten()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(1)
+            + 2;
+
+        queue!(
+            self.out,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0),
+            Print("embedded-keypad simulator\r\n\r\n"),
+        )?;
+
+        for row in 0..ROWS {
+            for col in 0..COLS {
+                let attribute = if self.held.contains(row, col) {
+                    Attribute::Reverse
+                } else {
+                    Attribute::NoReverse
+                };
+
+                queue!(
+                    self.out,
+                    Print(" "),
+                    SetAttribute(attribute),
+                    Print(format_args!("{:^width$}", self.labels[row][col])),
+                    SetAttribute(Attribute::Reset),
+                )?;
+            }
+
+            queue!(self.out, Print("   "))?;
+
+            for col in 0..COLS {
+                let key = self.bindings.key(row, col).unwrap_or(' ');
+                queue!(self.out, Print(format_args!(" {}", key)))?;
+            }
+
+            queue!(self.out, Print("\r\n\r\n"))?;
+        }
+
+        let mode = match (self.toggle, self.releases) {
+            (true, _) => "toggle",
+            (false, true) => "hold",
+            (false, false) => "tap",
+        };
+
+        queue!(
+            self.out,
+            Print(format_args!(
+                "Mode: {} (Tab to switch, Esc to quit)\r\n\r\n",
+                mode
+            ))
+        )?;
+
+        for line in self.display.lines() {
+            queue!(self.out, Print(line), Print("\r\n"))?;
+        }
+
+        self.out.flush()
+    }
+}
+
+impl<K, const COLS: usize, const ROWS: usize> Drop for Simulator<K, COLS, ROWS> {
+    fn drop(&mut self) {
+        if self.releases {
+            let _ = execute!(self.out, PopKeyboardEnhancementFlags);
+        }
+
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl<K, const COLS: usize, const ROWS: usize> Keypad for Simulator<K, COLS, ROWS>
+where
+    K: Copy + PartialEq + fmt::Display,
+{
+    type Key = K;
+    type Error = Error;
+
+    fn key_is_pressed(&mut self) -> Result<bool, Error> {
+        Ok(!self.update()?.is_empty())
+    }
+
+    fn read(&mut self) -> Result<Option<K>, Error> {
+        Ok(self.read_multi()?.and_then(|keys| keys.as_array()[0]))
+    }
+
+    fn read_multi(&mut self) -> Result<Option<Keys<K>>, Error> {
+        let keys = self.update()?;
+        let keys = keys.iter().map(|(row, col)| self.keymap[row][col]);
+
+        Ok(Keys::collect(keys.take(4)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bindings() {
+        let bindings = Bindings::parse(
+            "
+            # A 2x3 keypad, with one position unbound.
+            1 2 3
+
+            Q . e
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(bindings.position('q'), Some((1, 0)));
+        assert_eq!(bindings.position('E'), Some((1, 2)));
+        assert_eq!(bindings.key(0, 2), Some('3'));
+        assert_eq!(bindings.key(1, 1), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Bindings::parse("1 2\nab c"),
+            Err(ConfigError::InvalidKey(2, "ab".to_string()))
+        );
+        assert_eq!(
+            Bindings::parse("1 2 3 4 5 6 7 8 9"),
+            Err(ConfigError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn bind_replaces() {
+        let bindings = Bindings::new().bind('a', 0, 0).bind('A', 1, 1);
+        assert_eq!(bindings.position('a'), Some((1, 1)));
+        assert_eq!(bindings.key(0, 0), None);
+    }
+}
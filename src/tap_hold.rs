@@ -0,0 +1,361 @@
+use crate::{Clock, EventQueue, KeyEvent, Overflow};
+
+/// A key which acts as one key when tapped, and another while held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DualKey<K = u8> {
+    /// The key as reported by the keypad.
+    pub key: K,
+    /// The key reported when the key is tapped.
+    pub tap: K,
+    /// The key reported while the key is held, such as a modifier.
+    pub hold: K,
+}
+
+/// Turns [DualKey]s into their tap or hold keys, based on the [KeyEvent]s of
+/// a keypad. Up to `N` events are queued, up to `N` events can be delayed
+/// while a key is undecided, and up to `N` dual keys can be held at once. A
+/// dual key which becomes a hold while `N` others are held is reported as a
+/// tap instead.
+///
+/// A dual key is a hold if it is held for longer than the tapping term, or if
+/// another key is both pressed and released while it is held (permissive
+/// hold). Otherwise, it is a tap when it is released. The hold key is pressed
+/// when this is decided, and released when the dual key is released. The tap
+/// key is pressed and released when the dual key is released.
+///
+/// Events which occur while a dual key is undecided are delayed until it has
+/// been decided, so that they are reported after the tap or hold key is
+/// pressed.
+///
+/// # Examples
+///
+/// ```ignore
+/// // Tap hash for hash, or hold it to use it as shift.
+/// const DUAL_KEYS: &[DualKey<Key>] = &[DualKey {
+///     key: Key::Hash,
+///     tap: Key::Hash,
+///     hold: Key::F1,
+/// }];
+///
+/// let mut tap_hold: TapHold<_, 8, Key> = TapHold::new(|| timer.millis(), 200, DUAL_KEYS);
+///
+/// while let Some(event) = events.next_event() {
+///     tap_hold.process(event);
+/// }
+///
+/// tap_hold.poll();
+///
+/// while let Some(event) = tap_hold.next_event() {
+///     handle(event);
+/// }
+/// ```
+pub struct TapHold<'a, C: Clock, const N: usize, K: Copy = u8> {
+    clock: C,
+    tapping_term: u32,
+    dual_keys: &'a [DualKey<K>],
+    pending: Option<(DualKey<K>, u32)>,
+    holding: [Option<DualKey<K>>; N],
+    delayed: EventQueue<KeyEvent<K>, N>,
+    queue: EventQueue<KeyEvent<K>, N>,
+}
+
+impl<'a, C: Clock, const N: usize, K: Copy + PartialEq> TapHold<'a, C, N, K> {
+    /// Create a processor for the given dual keys, which become holds once
+    /// held for `tapping_term` ticks.
+    pub fn new(clock: C, tapping_term: u32, dual_keys: &'a [DualKey<K>]) -> Self {
+        Self {
+            clock,
+            tapping_term,
+            dual_keys,
+            pending: None,
+            holding: [None; N],
+            delayed: EventQueue::new(Overflow::DropOldest),
+            queue: EventQueue::new(Overflow::DropOldest),
+        }
+    }
+
+    /// Update the keys in progress with an event from the keypad.
+    pub fn process(&mut self, event: KeyEvent<K>) {
+        if let Some((dual, _)) = self.pending {
+            match event {
+                KeyEvent::Released(key) if key == dual.key => {
+                    self.pending = None;
+                    self.queue.push(KeyEvent::Pressed(dual.tap));
+                    self.queue.push(KeyEvent::Released(dual.tap));
+                    self.flush();
+                }
+                KeyEvent::Released(key) if self.was_delayed(key) => {
+                    self.delayed.push(event);
+                    self.hold(dual);
+                    self.flush();
+                }
+                _ => {
+                    self.delayed.push(event);
+                }
+            }
+
+            return;
+        }
+
+        match event {
+            KeyEvent::Pressed(key) => match self.dual_keys.iter().find(|dual| dual.key == key) {
+                Some(&dual) => self.pending = Some((dual, self.clock.now())),
+                None => {
+                    self.queue.push(event);
+                }
+            },
+            KeyEvent::Released(key) | KeyEvent::Repeated(key) => {
+                let holding = self
+                    .holding
+                    .iter_mut()
+                    .find(|dual| dual.is_some_and(|dual| dual.key == key));
+
+                match holding {
+                    Some(slot) if matches!(event, KeyEvent::Released(_)) => {
+                        if let Some(dual) = slot.take() {
+                            self.queue.push(KeyEvent::Released(dual.hold));
+                        }
+                    }
+                    Some(_) => (),
+                    // A dual key which was tapped because every slot was full.
+                    None if self.dual_keys.iter().any(|dual| dual.key == key) => (),
+                    None => {
+                        self.queue.push(event);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decide a key which has been held for longer than the tapping term.
+    /// This should be called regularly, even when there are no new events.
+    pub fn poll(&mut self) {
+        if let Some((dual, pressed_at)) = self.pending {
+            if self.clock.since(pressed_at) >= self.tapping_term {
+                self.hold(dual);
+                self.flush();
+            }
+        }
+    }
+
+    /// Remove the oldest event from the queue.
+    pub fn next_event(&mut self) -> Option<KeyEvent<K>> {
+        self.queue.pop()
+    }
+
+    fn hold(&mut self, dual: DualKey<K>) {
+        self.pending = None;
+
+        match self.holding.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(dual);
+                self.queue.push(KeyEvent::Pressed(dual.hold));
+            }
+            None => {
+                self.queue.push(KeyEvent::Pressed(dual.tap));
+                self.queue.push(KeyEvent::Released(dual.tap));
+            }
+        }
+    }
+
+    fn was_delayed(&self, key: K) -> bool {
+        self.delayed
+            .iter()
+            .any(|&event| event == KeyEvent::Pressed(key))
+    }
+
+    /// Process the events which were delayed while a key was undecided.
+    fn flush(&mut self) {
+        let mut delayed =
+            core::mem::replace(&mut self.delayed, EventQueue::new(Overflow::DropOldest));
+
+        while let Some(event) = delayed.pop() {
+            self.process(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::testing::drain;
+
+    const DUAL_KEYS: &[DualKey] = &[
+        DualKey {
+            key: 1,
+            tap: 10,
+            hold: 11,
+        },
+        DualKey {
+            key: 2,
+            tap: 20,
+            hold: 21,
+        },
+    ];
+
+    #[test]
+    fn tap() {
+        let now = Cell::new(0);
+        let mut tap_hold: TapHold<_, 8> = TapHold::new(|| now.get(), 200, DUAL_KEYS);
+
+        tap_hold.process(KeyEvent::Pressed(1));
+        now.set(199);
+        tap_hold.poll();
+        tap_hold.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| tap_hold.next_event()),
+            [
+                Some(KeyEvent::Pressed(10)),
+                Some(KeyEvent::Released(10)),
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn hold() {
+        let now = Cell::new(0);
+        let mut tap_hold: TapHold<_, 8> = TapHold::new(|| now.get(), 200, DUAL_KEYS);
+
+        tap_hold.process(KeyEvent::Pressed(1));
+        tap_hold.process(KeyEvent::Pressed(3));
+        now.set(200);
+        tap_hold.poll();
+        tap_hold.process(KeyEvent::Released(3));
+        tap_hold.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| tap_hold.next_event()),
+            [
+                Some(KeyEvent::Pressed(11)),
+                Some(KeyEvent::Pressed(3)),
+                Some(KeyEvent::Released(3)),
+                Some(KeyEvent::Released(11)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn permissive_hold() {
+        let now = Cell::new(0);
+        let mut tap_hold: TapHold<_, 8> = TapHold::new(|| now.get(), 200, DUAL_KEYS);
+
+        tap_hold.process(KeyEvent::Pressed(1));
+        tap_hold.process(KeyEvent::Pressed(3));
+        tap_hold.process(KeyEvent::Released(3));
+        tap_hold.process(KeyEvent::Released(1));
+
+        assert_eq!(
+            drain(|| tap_hold.next_event()),
+            [
+                Some(KeyEvent::Pressed(11)),
+                Some(KeyEvent::Pressed(3)),
+                Some(KeyEvent::Released(3)),
+                Some(KeyEvent::Released(11)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn rollover() {
+        let now = Cell::new(0);
+        let mut tap_hold: TapHold<_, 8> = TapHold::new(|| now.get(), 200, DUAL_KEYS);
+
+        tap_hold.process(KeyEvent::Pressed(1));
+        tap_hold.process(KeyEvent::Pressed(3));
+        tap_hold.process(KeyEvent::Released(1));
+        tap_hold.process(KeyEvent::Released(3));
+
+        assert_eq!(
+            drain(|| tap_hold.next_event()),
+            [
+                Some(KeyEvent::Pressed(10)),
+                Some(KeyEvent::Released(10)),
+                Some(KeyEvent::Pressed(3)),
+                Some(KeyEvent::Released(3)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn holds_up_to_n_keys() {
+        let now = Cell::new(0);
+        let mut tap_hold: TapHold<_, 2> = TapHold::new(|| now.get(), 200, DUAL_KEYS);
+
+        tap_hold.process(KeyEvent::Pressed(1));
+        now.set(200);
+        tap_hold.poll();
+        tap_hold.process(KeyEvent::Pressed(2));
+        now.set(400);
+        tap_hold.poll();
+
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Pressed(11)));
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Pressed(21)));
+
+        tap_hold.process(KeyEvent::Released(1));
+        tap_hold.process(KeyEvent::Released(2));
+
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Released(11)));
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Released(21)));
+    }
+
+    #[test]
+    fn keys_past_n_are_tapped() {
+        const DUAL_KEYS: &[DualKey] = &[
+            DualKey {
+                key: 1,
+                tap: 10,
+                hold: 11,
+            },
+            DualKey {
+                key: 2,
+                tap: 20,
+                hold: 21,
+            },
+            DualKey {
+                key: 3,
+                tap: 30,
+                hold: 31,
+            },
+        ];
+
+        let now = Cell::new(0);
+        let mut tap_hold: TapHold<_, 2> = TapHold::new(|| now.get(), 200, DUAL_KEYS);
+
+        tap_hold.process(KeyEvent::Pressed(1));
+        now.set(200);
+        tap_hold.poll();
+        tap_hold.process(KeyEvent::Pressed(2));
+        now.set(400);
+        tap_hold.poll();
+
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Pressed(11)));
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Pressed(21)));
+
+        tap_hold.process(KeyEvent::Pressed(3));
+        now.set(600);
+        tap_hold.poll();
+
+        // Every slot is full, so the third key is tapped rather than held.
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Pressed(30)));
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Released(30)));
+
+        tap_hold.process(KeyEvent::Released(3));
+        tap_hold.process(KeyEvent::Released(1));
+
+        assert_eq!(tap_hold.next_event(), Some(KeyEvent::Released(11)));
+        assert_eq!(tap_hold.next_event(), None);
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers shared by the tests of the event processors.
+
+/// Take the next `M` items from a queue, so that its whole contents can be
+/// compared with an array, padded with [None].
+pub fn drain<T, const M: usize>(mut next: impl FnMut() -> Option<T>) -> [Option<T>; M] {
+    core::array::from_fn(|_| next())
+}
+
+/// Press and release each of the given keys on an event processor, returning
+/// what its `process` method returned for the last press.
+macro_rules! tap {
+    ($processor:expr, $keys:expr) => {{
+        let mut last = Default::default();
+
+        for &key in $keys {
+            last = $processor.process($crate::KeyEvent::Pressed(key));
+            $processor.process($crate::KeyEvent::Released(key));
+        }
+
+        last
+    }};
+}
+
+pub(crate) use tap;
@@ -0,0 +1,53 @@
+//! Text entry using the keys of a numeric keypad.
+
+mod multitap;
+pub mod t9;
+
+pub use multitap::{Case, MultiTap};
+
+use crate::Key::{self, *};
+
+/// The characters entered by each key, in the order they are cycled through.
+pub type CharMap<'a, K = Key> = &'a [(K, &'a str)];
+
+/// The standard letters for English, as printed on telephone keypads.
+pub const ENGLISH: CharMap<'static> = &[
+    (Num1, ".,?!'\"-1"),
+    (Num2, "abc2"),
+    (Num3, "def3"),
+    (Num4, "ghi4"),
+    (Num5, "jkl5"),
+    (Num6, "mno6"),
+    (Num7, "pqrs7"),
+    (Num8, "tuv8"),
+    (Num9, "wxyz9"),
+    (Num0, " 0"),
+];
+
+/// The letters for German, including umlauts and ß.
+pub const GERMAN: CharMap<'static> = &[
+    (Num1, ".,?!'\"-1"),
+    (Num2, "abcä2"),
+    (Num3, "def3"),
+    (Num4, "ghi4"),
+    (Num5, "jkl5"),
+    (Num6, "mnoö6"),
+    (Num7, "pqrsß7"),
+    (Num8, "tuvü8"),
+    (Num9, "wxyz9"),
+    (Num0, " 0"),
+];
+
+/// The letters for French, including common accented letters.
+pub const FRENCH: CharMap<'static> = &[
+    (Num1, ".,?!'\"-1"),
+    (Num2, "abcàâç2"),
+    (Num3, "deféèêë3"),
+    (Num4, "ghiîï4"),
+    (Num5, "jkl5"),
+    (Num6, "mnoô6"),
+    (Num7, "pqrs7"),
+    (Num8, "tuvùûü8"),
+    (Num9, "wxyz9"),
+    (Num0, " 0"),
+];
@@ -0,0 +1,303 @@
+use heapless::String;
+
+use super::CharMap;
+use crate::{Clock, Key, KeyEvent};
+
+/// The case of the letters entered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    #[default]
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pending<K> {
+    key: K,
+    index: usize,
+    pressed_at: u32,
+}
+
+/// Multi-tap text entry, as used on telephones. Pressing a key enters the
+/// first of its characters, and pressing it again before the timeout replaces
+/// it with the next. The character is committed when the timeout passes, or
+/// when another key is pressed. Up to `N` bytes of text are entered, and
+/// characters which would not fit are skipped while cycling.
+///
+/// The character being cycled is kept in the text, so the text can always be
+/// displayed as it is.
+///
+/// # Examples
+///
+/// ```ignore
+/// use embedded_keypad::text::{self, MultiTap};
+///
+/// let mut input: MultiTap<_, 16> = MultiTap::new(|| timer.millis(), 1000, text::ENGLISH)
+///     .with_backspace(Key::Star)
+///     .with_case_toggle(Key::Hash);
+///
+/// while let Some(event) = events.next_event() {
+///     input.process(event);
+/// }
+///
+/// input.poll();
+/// display.show(input.text());
+/// ```
+pub struct MultiTap<'a, C: Clock, const N: usize, K: Copy = Key> {
+    clock: C,
+    timeout: u32,
+    chars: CharMap<'a, K>,
+    backspace: Option<K>,
+    case_toggle: Option<K>,
+    case: Case,
+    pending: Option<Pending<K>>,
+    text: String<N>,
+}
+
+impl<'a, C: Clock, const N: usize, K: Copy + PartialEq> MultiTap<'a, C, N, K> {
+    /// Create an empty text entry, which commits each character once its key
+    /// has not been pressed for `timeout` ticks.
+    pub fn new(clock: C, timeout: u32, chars: CharMap<'a, K>) -> Self {
+        Self {
+            clock,
+            timeout,
+            chars,
+            backspace: None,
+            case_toggle: None,
+            case: Case::Lower,
+            pending: None,
+            text: String::new(),
+        }
+    }
+
+    /// Delete the last character when `key` is pressed.
+    pub fn with_backspace(mut self, key: K) -> Self {
+        self.backspace = Some(key);
+        self
+    }
+
+    /// Switch between lower and upper case when `key` is pressed.
+    pub fn with_case_toggle(mut self, key: K) -> Self {
+        self.case_toggle = Some(key);
+        self
+    }
+
+    /// Change the characters entered by each key, such as to switch language.
+    /// Any pending character is committed first.
+    pub fn set_chars(&mut self, chars: CharMap<'a, K>) {
+        self.commit();
+        self.chars = chars;
+    }
+
+    /// Update the text with an event from the keypad. Only key presses are
+    /// used.
+    pub fn process(&mut self, event: KeyEvent<K>) {
+        let KeyEvent::Pressed(key) = event else {
+            return;
+        };
+
+        if Some(key) == self.backspace {
+            self.pending = None;
+            self.text.pop();
+            return;
+        }
+
+        if Some(key) == self.case_toggle {
+            self.commit();
+            self.case = match self.case {
+                Case::Lower => Case::Upper,
+                Case::Upper => Case::Lower,
+            };
+            return;
+        }
+
+        let Some(&(_, chars)) = self.chars.iter().find(|(k, _)| *k == key) else {
+            self.commit();
+            return;
+        };
+
+        let now = self.clock.now();
+        let count = chars.chars().count();
+        let (start, old) = match self.pending {
+            Some(pending)
+                if pending.key == key && now.wrapping_sub(pending.pressed_at) < self.timeout =>
+            {
+                (pending.index + 1, self.text.chars().next_back())
+            }
+            _ => (0, None),
+        };
+
+        self.pending = None;
+
+        // Characters which would not fit are skipped, so the pending character
+        // is only replaced by one which does.
+        let room = N - self.text.len() + old.map_or(0, char::len_utf8);
+        let next = (0..count)
+            .map(|i| (start + i) % count)
+            .filter_map(|index| Some((index, self.apply_case(chars.chars().nth(index)?))))
+            .find(|(_, ch)| ch.len_utf8() <= room);
+
+        let Some((index, ch)) = next else {
+            return;
+        };
+
+        if old.is_some() {
+            self.text.pop();
+        }
+
+        if self.text.push(ch).is_ok() {
+            self.pending = Some(Pending {
+                key,
+                index,
+                pressed_at: now,
+            });
+        }
+    }
+
+    /// Commit the pending character once the timeout has passed. This should
+    /// be called regularly, even when there are no new events.
+    pub fn poll(&mut self) {
+        if let Some(pending) = self.pending {
+            if self.clock.since(pending.pressed_at) >= self.timeout {
+                self.commit();
+            }
+        }
+    }
+
+    /// Commit the pending character, so that the next press starts a new one.
+    pub fn commit(&mut self) {
+        self.pending = None;
+    }
+
+    /// The text entered, including any pending character.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The character which is still being cycled, which is the last in the
+    /// text.
+    pub fn pending(&self) -> Option<char> {
+        self.pending.and_then(|_| self.text.chars().next_back())
+    }
+
+    /// The case of the letters being entered.
+    pub fn case(&self) -> Case {
+        self.case
+    }
+
+    /// Remove all of the text.
+    pub fn clear(&mut self) {
+        self.pending = None;
+        self.text.clear();
+    }
+
+    /// Take the text entered, leaving the entry empty.
+    pub fn take(&mut self) -> String<N> {
+        self.pending = None;
+        core::mem::take(&mut self.text)
+    }
+
+    fn apply_case(&self, ch: char) -> char {
+        let mut upper = ch.to_uppercase();
+
+        match (self.case, upper.next(), upper.next()) {
+            (Case::Upper, Some(upper), None) => upper,
+            _ => ch,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::testing::tap;
+    use crate::text::{ENGLISH, GERMAN};
+
+    #[test]
+    fn cycling() {
+        let now = Cell::new(0);
+        let mut input: MultiTap<_, 8> = MultiTap::new(|| now.get(), 1000, ENGLISH);
+
+        tap!(input, &[Key::Num2]);
+        assert_eq!(input.text(), "a");
+        assert_eq!(input.pending(), Some('a'));
+
+        tap!(input, &[Key::Num2; 2]);
+        assert_eq!(input.text(), "c");
+
+        tap!(input, &[Key::Num2; 2]);
+        assert_eq!(input.text(), "a");
+
+        // Another key commits the pending character.
+        tap!(input, &[Key::Num3; 2]);
+        assert_eq!(input.text(), "ae");
+    }
+
+    #[test]
+    fn timeout_commits() {
+        let now = Cell::new(0);
+        let mut input: MultiTap<_, 8> = MultiTap::new(|| now.get(), 1000, ENGLISH);
+
+        tap!(input, &[Key::Num2; 2]);
+        now.set(999);
+        input.poll();
+        assert_eq!(input.pending(), Some('b'));
+
+        now.set(1000);
+        input.poll();
+        assert_eq!(input.pending(), None);
+
+        tap!(input, &[Key::Num2]);
+        assert_eq!(input.text(), "ba");
+    }
+
+    #[test]
+    fn backspace() {
+        let now = Cell::new(0);
+        let mut input: MultiTap<_, 8> =
+            MultiTap::new(|| now.get(), 1000, ENGLISH).with_backspace(Key::Star);
+
+        tap!(input, &[Key::Num2]);
+        tap!(input, &[Key::Num3]);
+        tap!(input, &[Key::Star]);
+        assert_eq!(input.text(), "a");
+
+        // The next press starts a new character, rather than cycling.
+        tap!(input, &[Key::Num2]);
+        assert_eq!(input.text(), "aa");
+    }
+
+    #[test]
+    fn case() {
+        let now = Cell::new(0);
+        let mut input: MultiTap<_, 8> =
+            MultiTap::new(|| now.get(), 1000, GERMAN).with_case_toggle(Key::Hash);
+
+        tap!(input, &[Key::Num2]);
+        tap!(input, &[Key::Hash]);
+        assert_eq!(input.case(), Case::Upper);
+
+        tap!(input, &[Key::Num2; 4]);
+        assert_eq!(input.text(), "aÄ");
+
+        tap!(input, &[Key::Num3]);
+        assert_eq!(input.text(), "aÄD");
+    }
+
+    #[test]
+    fn characters_which_do_not_fit_are_skipped() {
+        let now = Cell::new(0);
+        let mut input: MultiTap<_, 1> = MultiTap::new(|| now.get(), 1000, GERMAN);
+
+        tap!(input, &[Key::Num2; 3]);
+        assert_eq!(input.text(), "c");
+
+        tap!(input, &[Key::Num2]);
+        assert_eq!(input.text(), "2");
+
+        tap!(input, &[Key::Num3]);
+        assert_eq!(input.text(), "2");
+    }
+}
@@ -0,0 +1,522 @@
+//! Predictive text entry, where each key is pressed once per letter and the
+//! word is found in a dictionary.
+//!
+//! # Dictionary format
+//!
+//! A dictionary is a byte slice which can be stored in flash, for example
+//! using `include_bytes!`. It starts with the three bytes `T9\x01`, followed
+//! by each word as a length byte and that many lowercase ASCII letters. Words
+//! are sorted by their digit sequence, and words with the same sequence are in
+//! order of preference.
+//!
+//! With the `std` feature, `compile` creates a dictionary from a list of
+//! words, and the `t9-compile` binary does the same from a file:
+//!
+//! ```text
+//! cargo run --features std --bin t9-compile -- words.txt dictionary.t9
+//! ```
+
+use heapless::{String, Vec};
+
+use crate::{Key, KeyEvent};
+
+/// The bytes at the start of every dictionary.
+pub const MAGIC: [u8; 3] = *b"T9\x01";
+
+/// The digit key used to enter a letter, according to the standard telephone
+/// layout.
+pub fn letter_digit(letter: u8) -> Option<u8> {
+    match letter.to_ascii_lowercase() {
+        b'a'..=b'c' => Some(2),
+        b'd'..=b'f' => Some(3),
+        b'g'..=b'i' => Some(4),
+        b'j'..=b'l' => Some(5),
+        b'm'..=b'o' => Some(6),
+        b'p'..=b's' => Some(7),
+        b't'..=b'v' => Some(8),
+        b'w'..=b'z' => Some(9),
+        _ => None,
+    }
+}
+
+/// Determines whether a word is entered using the given digits.
+pub fn matches(word: &str, digits: &[u8]) -> bool {
+    word.len() == digits.len()
+        && word
+            .bytes()
+            .zip(digits)
+            .all(|(letter, &digit)| letter_digit(letter) == Some(digit))
+}
+
+/// The reason a dictionary could not be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictionaryError {
+    /// The dictionary does not start with [MAGIC].
+    BadHeader,
+    /// The last word is cut short.
+    Truncated,
+    /// A word contains something other than lowercase ASCII letters.
+    InvalidWord,
+    /// The words are not sorted by their digit sequence.
+    Unsorted,
+}
+
+/// A dictionary of words for predictive text, stored in the compact format
+/// described in the [module documentation](self).
+#[derive(Debug, Clone, Copy)]
+pub struct Dictionary<'a> {
+    words: &'a [u8],
+}
+
+impl<'a> Dictionary<'a> {
+    /// Use the given bytes as a dictionary, checking that they are valid and
+    /// that the words are sorted by their digit sequence.
+    pub fn new(data: &'a [u8]) -> Result<Self, DictionaryError> {
+        let words = data
+            .strip_prefix(&MAGIC)
+            .ok_or(DictionaryError::BadHeader)?;
+
+        let mut rest = words;
+        let mut previous: &[u8] = &[];
+
+        while let Some((&len, tail)) = rest.split_first() {
+            let word = tail.get(..len as usize).ok_or(DictionaryError::Truncated)?;
+
+            if word.is_empty() || !word.iter().all(u8::is_ascii_lowercase) {
+                return Err(DictionaryError::InvalidWord);
+            }
+
+            let digits = |word: &'a [u8]| word.iter().map(|&letter| letter_digit(letter));
+
+            if digits(previous).gt(digits(word)) {
+                return Err(DictionaryError::Unsorted);
+            }
+
+            previous = word;
+            rest = &tail[len as usize..];
+        }
+
+        Ok(Self { words })
+    }
+
+    /// Iterate over every word in the dictionary.
+    pub fn words(&self) -> impl Iterator<Item = &'a str> {
+        let mut rest = self.words;
+
+        core::iter::from_fn(move || {
+            let (&len, tail) = rest.split_first()?;
+            let (word, tail) = tail.split_at(len as usize);
+            rest = tail;
+
+            // Checked to be ASCII by `Dictionary::new`.
+            core::str::from_utf8(word).ok()
+        })
+    }
+
+    /// Iterate over the words entered using the given digits, in order of
+    /// preference.
+    pub fn lookup<'d>(&self, digits: &'d [u8]) -> impl Iterator<Item = &'a str> + 'd
+    where
+        'a: 'd,
+    {
+        self.words()
+            .skip_while(move |word| !matches(word, digits))
+            .take_while(move |word| matches(word, digits))
+    }
+}
+
+/// The reason a dictionary could not be compiled.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileError {
+    /// The word contains something other than ASCII letters.
+    InvalidWord(std::string::String),
+    /// The word is longer than 255 letters.
+    TooLong(std::string::String),
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for CompileError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CompileError::InvalidWord(word) => write!(f, "invalid word: {:?}", word),
+            CompileError::TooLong(word) => write!(f, "word too long: {:?}", word),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CompileError {}
+
+/// Compile a list of words, in order of preference, into a dictionary. Words
+/// are converted to lowercase, and repeated words are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_keypad::text::t9::{compile, Dictionary};
+///
+/// let data = compile(["good", "home", "gone", "hood"]).unwrap();
+/// let dictionary = Dictionary::new(&data).unwrap();
+///
+/// let words: Vec<_> = dictionary.lookup(&[4, 6, 6, 3]).collect();
+/// assert_eq!(words, ["good", "home", "gone", "hood"]);
+/// ```
+#[cfg(feature = "std")]
+pub fn compile<'w, I>(words: I) -> Result<std::vec::Vec<u8>, CompileError>
+where
+    I: IntoIterator<Item = &'w str>,
+{
+    use std::collections::HashSet;
+    use std::vec::Vec;
+
+    let mut seen = HashSet::new();
+    let mut list: Vec<(Vec<u8>, std::string::String)> = Vec::new();
+
+    for word in words {
+        let word = word.to_ascii_lowercase();
+
+        if word.is_empty() || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err(CompileError::InvalidWord(word));
+        }
+
+        if word.len() > u8::MAX as usize {
+            return Err(CompileError::TooLong(word));
+        }
+
+        if seen.insert(word.clone()) {
+            let digits = word.bytes().filter_map(letter_digit).collect();
+            list.push((digits, word));
+        }
+    }
+
+    list.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut data = MAGIC.to_vec();
+
+    for (_, word) in list {
+        data.push(word.len() as u8);
+        data.extend_from_slice(word.as_bytes());
+    }
+
+    Ok(data)
+}
+
+/// The reason a user word could not be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddWordError {
+    /// There is no space for more user words.
+    Full,
+    /// The word is too long, or contains something other than ASCII letters.
+    InvalidWord,
+}
+
+/// Predictive text entry using a [Dictionary]. Each digit from 2 to 9 adds a
+/// letter to the word being composed, and the matching words are offered as
+/// candidates. Up to `N` bytes of text are entered, words can be up to `W`
+/// letters long, and up to `U` user words can be added.
+///
+/// By default, [Key::Star] moves to the next candidate and [Key::Num0]
+/// accepts the candidate followed by a space. Any other key accepts the
+/// candidate. User words are offered before the words in the dictionary.
+///
+/// A candidate is only accepted if it fits in the text, along with its space.
+/// Otherwise, the text is left unchanged and the word being composed is kept,
+/// so that it can be removed with backspace.
+///
+/// # Examples
+///
+/// ```ignore
+/// use embedded_keypad::text::t9::{Dictionary, Predictive};
+///
+/// static DICTIONARY: &[u8] = include_bytes!("dictionary.t9");
+///
+/// let dictionary = Dictionary::new(DICTIONARY).unwrap();
+/// let mut input: Predictive<64> = Predictive::new(dictionary).with_backspace(Key::Hash);
+///
+/// while let Some(event) = events.next_event() {
+///     input.process(event);
+/// }
+///
+/// display.show(input.text(), input.candidate());
+/// ```
+pub struct Predictive<'a, const N: usize, const W: usize = 16, const U: usize = 8> {
+    dictionary: Dictionary<'a>,
+    user_words: Vec<String<W>, U>,
+    next: Key,
+    space: Key,
+    backspace: Option<Key>,
+    digits: Vec<u8, W>,
+    candidate: usize,
+    text: String<N>,
+}
+
+impl<'a, const N: usize, const W: usize, const U: usize> Predictive<'a, N, W, U> {
+    /// Create an empty text entry which offers words from the given
+    /// dictionary.
+    pub fn new(dictionary: Dictionary<'a>) -> Self {
+        Self {
+            dictionary,
+            user_words: Vec::new(),
+            next: Key::Star,
+            space: Key::Num0,
+            backspace: None,
+            digits: Vec::new(),
+            candidate: 0,
+            text: String::new(),
+        }
+    }
+
+    /// Move to the next candidate when `key` is pressed.
+    pub fn with_next(mut self, key: Key) -> Self {
+        self.next = key;
+        self
+    }
+
+    /// Accept the candidate followed by a space when `key` is pressed.
+    pub fn with_space(mut self, key: Key) -> Self {
+        self.space = key;
+        self
+    }
+
+    /// Remove the last digit, or the last character of the text when no word
+    /// is being composed, when `key` is pressed.
+    pub fn with_backspace(mut self, key: Key) -> Self {
+        self.backspace = Some(key);
+        self
+    }
+
+    /// Add a word which is offered before the words in the dictionary.
+    pub fn add_word(&mut self, word: &str) -> Result<(), AddWordError> {
+        if word.is_empty() || !word.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(AddWordError::InvalidWord);
+        }
+
+        let mut user_word = String::new();
+
+        for ch in word.chars() {
+            user_word
+                .push(ch.to_ascii_lowercase())
+                .map_err(|_| AddWordError::InvalidWord)?;
+        }
+
+        if self.user_words.contains(&user_word) {
+            return Ok(());
+        }
+
+        self.user_words
+            .push(user_word)
+            .map_err(|_| AddWordError::Full)
+    }
+
+    /// Update the text with an event from the keypad. Only key presses are
+    /// used.
+    pub fn process(&mut self, event: KeyEvent<Key>) {
+        let KeyEvent::Pressed(key) = event else {
+            return;
+        };
+
+        if key == self.next {
+            let count = self.candidates().count();
+
+            if count > 0 {
+                self.candidate = (self.candidate + 1) % count;
+            }
+        } else if Some(key) == self.backspace {
+            if self.digits.pop().is_none() {
+                self.text.pop();
+            }
+
+            self.candidate = 0;
+        } else if key == self.space {
+            self.accept(" ");
+        } else if let Some(digit @ 2..=9) = key.digit() {
+            if self.digits.push(digit).is_ok() {
+                self.candidate = 0;
+            }
+        } else {
+            self.commit();
+        }
+    }
+
+    /// Accept the current candidate, adding it to the text. If there is no
+    /// candidate, the digits are discarded. Returns false, keeping the word
+    /// being composed, if the candidate does not fit in the text.
+    pub fn commit(&mut self) -> bool {
+        self.accept("")
+    }
+
+    /// Accept the current candidate followed by `separator`, if both fit.
+    fn accept(&mut self, separator: &str) -> bool {
+        let mut text = self.text.clone();
+        let word = self.candidate().unwrap_or("");
+
+        if text.push_str(word).is_err() || text.push_str(separator).is_err() {
+            return false;
+        }
+
+        self.text = text;
+        self.digits.clear();
+        self.candidate = 0;
+        true
+    }
+
+    /// The word currently offered for the digits entered, if any match.
+    pub fn candidate(&self) -> Option<&str> {
+        self.candidates().nth(self.candidate)
+    }
+
+    /// Iterate over every word which matches the digits entered.
+    pub fn candidates(&self) -> impl Iterator<Item = &str> {
+        let digits = &self.digits[..];
+        let user = self
+            .user_words
+            .iter()
+            .map(String::as_str)
+            .filter(move |word| !digits.is_empty() && matches(word, digits));
+
+        let dictionary = self
+            .dictionary
+            .lookup(digits)
+            .filter(move |word| !digits.is_empty() && !self.user_words.iter().any(|w| w == word));
+
+        user.chain(dictionary)
+    }
+
+    /// The digits entered for the word being composed.
+    pub fn digits(&self) -> &[u8] {
+        &self.digits
+    }
+
+    /// The text entered, not including the word being composed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Remove all of the text, and the word being composed.
+    pub fn clear(&mut self) {
+        self.digits.clear();
+        self.candidate = 0;
+        self.text.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::tap;
+
+    const DICTIONARY: &[u8] = b"T9\x01\x01a\x02go\x02in\x04good\x04home\x04gone\x04hood\x03the";
+
+    fn dictionary() -> Dictionary<'static> {
+        Dictionary::new(DICTIONARY).unwrap()
+    }
+
+    #[test]
+    fn lookup() {
+        let dictionary = dictionary();
+
+        assert!(dictionary.lookup(&[4, 6]).eq(["go", "in"]));
+        assert!(dictionary.lookup(&[8, 4, 3]).eq(["the"]));
+        assert_eq!(dictionary.lookup(&[4]).next(), None);
+    }
+
+    #[test]
+    fn invalid_dictionaries() {
+        assert_eq!(
+            Dictionary::new(b"T8\x01").unwrap_err(),
+            DictionaryError::BadHeader
+        );
+        assert_eq!(
+            Dictionary::new(b"T9\x01\x03go").unwrap_err(),
+            DictionaryError::Truncated
+        );
+        assert_eq!(
+            Dictionary::new(b"T9\x01\x02Go").unwrap_err(),
+            DictionaryError::InvalidWord
+        );
+        assert_eq!(
+            Dictionary::new(b"T9\x01\x03the\x02go").unwrap_err(),
+            DictionaryError::Unsorted
+        );
+    }
+
+    #[test]
+    fn candidates() {
+        let mut input: Predictive<16> = Predictive::new(dictionary());
+
+        tap!(input, &[Key::Num4, Key::Num6, Key::Num6, Key::Num3]);
+        assert_eq!(input.digits(), [4, 6, 6, 3]);
+        assert!(input.candidates().eq(["good", "home", "gone", "hood"]));
+        assert_eq!(input.candidate(), Some("good"));
+
+        tap!(input, &[Key::Star]);
+        assert_eq!(input.candidate(), Some("home"));
+
+        tap!(input, &[Key::Star, Key::Star, Key::Star]);
+        assert_eq!(input.candidate(), Some("good"));
+
+        tap!(input, &[Key::Star, Key::Num0]);
+        assert_eq!(input.text(), "home ");
+        assert_eq!(input.candidate(), None);
+
+        // Any other key accepts the candidate without a space.
+        tap!(input, &[Key::Num2, Key::Hash]);
+        assert_eq!(input.text(), "home a");
+    }
+
+    #[test]
+    fn unknown_word_is_discarded() {
+        let mut input: Predictive<16> = Predictive::new(dictionary());
+
+        tap!(input, &[Key::Num9, Key::Num9]);
+        assert_eq!(input.candidate(), None);
+
+        input.commit();
+        assert_eq!(input.digits(), []);
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn backspace() {
+        let mut input: Predictive<16> = Predictive::new(dictionary()).with_backspace(Key::Clear);
+
+        tap!(input, &[Key::Num4, Key::Num6, Key::Num0]);
+        tap!(input, &[Key::Num8, Key::Num4, Key::Clear]);
+        assert_eq!(input.digits(), [8]);
+
+        tap!(input, &[Key::Clear, Key::Clear]);
+        assert_eq!(input.text(), "go");
+    }
+
+    #[test]
+    fn word_which_does_not_fit_is_kept() {
+        let mut input: Predictive<6> = Predictive::new(dictionary());
+
+        let digits = [Key::Num4, Key::Num6, Key::Num6, Key::Num3];
+
+        tap!(input, &digits);
+        tap!(input, &[Key::Num0]);
+        tap!(input, &digits);
+        tap!(input, &[Key::Star, Key::Num0]);
+        assert_eq!(input.text(), "good ");
+        assert_eq!(input.candidate(), Some("home"));
+        assert!(!input.commit());
+        assert_eq!(input.digits(), [4, 6, 6, 3]);
+    }
+
+    #[test]
+    fn user_words() {
+        let mut input: Predictive<16> = Predictive::new(dictionary());
+
+        assert_eq!(input.add_word("Hoof"), Ok(()));
+        assert_eq!(input.add_word("gone"), Ok(()));
+        assert_eq!(input.add_word("gone"), Ok(()));
+        assert_eq!(input.add_word("g0"), Err(AddWordError::InvalidWord));
+
+        tap!(input, &[Key::Num4, Key::Num6, Key::Num6, Key::Num3]);
+        assert!(input
+            .candidates()
+            .eq(["hoof", "gone", "good", "home", "hood"]));
+    }
+}
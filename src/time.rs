@@ -0,0 +1,28 @@
+/// A monotonic source of time, used by the parts of this crate which need to
+/// measure how long keys are held. Time is counted in ticks, the length of
+/// which is up to the implementation, and all durations are given in the same
+/// ticks. The count is expected to wrap around when it overflows.
+///
+/// Any `Fn() -> u32` can be used as a clock.
+///
+/// # Examples
+///
+/// ```ignore
+/// // Use a hardware timer counting in milliseconds.
+/// let clock = || timer.now().ticks();
+/// ```
+pub trait Clock {
+    /// The current time in ticks.
+    fn now(&self) -> u32;
+
+    /// The number of ticks which have passed since `then`.
+    fn since(&self, then: u32) -> u32 {
+        self.now().wrapping_sub(then)
+    }
+}
+
+impl<F: Fn() -> u32> Clock for F {
+    fn now(&self) -> u32 {
+        self()
+    }
+}